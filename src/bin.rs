@@ -6,8 +6,10 @@ extern crate error_chain;
 extern crate log;
 
 use clap::App;
+use clap::AppSettings;
 use clap::Arg;
 use clap::ArgMatches;
+use clap::SubCommand;
 use hackscanner_lib::*;
 
 use simplelog::ColorChoice;
@@ -15,22 +17,33 @@ use simplelog::ConfigBuilder;
 use simplelog::TerminalMode;
 use std::env;
 use std::path::Path;
+use std::thread;
 
 mod ui;
 
+/// Exit code if an internal error occurred, a rule is invalid (`rules check`) or a rule example
+/// failed (`rules test`)
+///
+/// This is the same code clap exits with for invalid command line arguments, so scripts only
+/// have to handle one error code
+const EXIT_CODE_ERROR: i32 = 1;
+
 fn main() {
-    if let Err(ref e) = run() {
-        use error_chain::ChainedError;
-        use std::io::Write; // trait which holds `display_chain`
-        let stderr = &mut ::std::io::stderr();
-        let errmsg = "Error writing to stderr";
+    match run() {
+        Ok(exit_code) => ::std::process::exit(exit_code),
+        Err(ref e) => {
+            use error_chain::ChainedError;
+            use std::io::Write; // trait which holds `display_chain`
+            let stderr = &mut ::std::io::stderr();
+            let errmsg = "Error writing to stderr";
 
-        writeln!(stderr, "{}", e.display_chain()).expect(errmsg);
-        ::std::process::exit(1);
+            writeln!(stderr, "{}", e.display_chain()).expect(errmsg);
+            ::std::process::exit(EXIT_CODE_ERROR);
+        }
     }
 }
 
-fn run() -> Result<(), Error> {
+fn run() -> Result<i32, Error> {
     let app = App::new("hackscanner")
         .version(env!("CARGO_PKG_VERSION"))
         .author("Daniel Corn <info@cundd.net>")
@@ -42,6 +55,7 @@ fn run() -> Result<(), Error> {
         .arg(Arg::with_name("v")
             .short("v")
             .multiple(true)
+            .global(true)
             .help(get_verbosity_help()))
         .arg(Arg::with_name("min-severity")
             .short("m")
@@ -51,39 +65,238 @@ fn run() -> Result<(), Error> {
             .short("q")
             .long("quiet")
             .alias("silent")
-            .help("Do not write to standard output if no violations > min-severity are found"))
+            .help("Do not write to standard output if no violations > min-severity are found (the json, sarif and junit reports are always written)"))
         .arg(Arg::with_name("validate")
             .short("l")
             .long("validate")
             .takes_value(true)
             .value_name("test-path")
             .help("Check if the given test-path would create a violation (ignores if the path exists)"))
+        .arg(Arg::with_name("format")
+            .short("f")
+            .long("format")
+            .takes_value(true)
+            .possible_values(report::Format::names())
+            .default_value("text")
+            .help("Sets the output format of the scan results"))
+        .arg(Arg::with_name("fail-on")
+            .long("fail-on")
+            .takes_value(true)
+            .value_name("SEVERITY")
+            .possible_values(&["CRITICAL", "MAJOR", "MINOR", "NOTICE"])
+            .case_insensitive(true)
+            .help("Exit with a non-zero code if a violation with the given severity or higher is found (NOTICE = 2, MINOR = 3, MAJOR = 4, CRITICAL = 5)"))
+        .arg(Arg::with_name("jobs")
+            .short("j")
+            .long("jobs")
+            .takes_value(true)
+            .value_name("N")
+            .help("Number of threads used to rate the files (defaults to the number of CPUs)"))
+        .arg(Arg::with_name("stream")
+            .short("s")
+            .long("stream")
+            .help("Print the violations while the files are scanned and the summary at the end (text format only)"))
+        .arg(Arg::with_name("read-limit")
+            .long("read-limit")
+            .takes_value(true)
+            .value_name("SIZE")
+            .help("Number of bytes read from the beginning of each file, or the chunk size with --chunked (e.g. 512K, default 4M)"))
+        .arg(Arg::with_name("read-tail")
+            .long("read-tail")
+            .takes_value(true)
+            .value_name("SIZE")
+            .help("Number of bytes additionally read from the end of each file (e.g. 64K)"))
+        .arg(Arg::with_name("chunked")
+            .long("chunked")
+            .help("Scan the whole content of each file in overlapping chunks of --read-limit bytes"))
+        .arg(Arg::with_name("chunk-overlap")
+            .long("chunk-overlap")
+            .takes_value(true)
+            .value_name("SIZE")
+            .help("Number of bytes consecutive chunks overlap (default 4K)"))
+        .arg(Arg::with_name("allowlist")
+            .long("allowlist")
+            .takes_value(true)
+            .value_name("FILE")
+            .multiple(true)
+            .number_of_values(1)
+            .help("File with SHA-256 hashes of known-good files (e.g. a manifest of a clean vendor release), which are ignored (can be given multiple times)"))
+        .arg(Arg::with_name("no-builtin")
+            .long("no-builtin")
+            .global(true)
+            .help("Do not load the builtin rules (only the rules from the configuration files are used)"))
+        .arg(Arg::with_name("rule-pack")
+            .long("rule-pack")
+            .takes_value(true)
+            .value_name("PATH")
+            .multiple(true)
+            .number_of_values(1)
+            .global(true)
+            .help("Rule pack file or directory of rule packs, which replace the embedded builtin packs with a lower version (can be given multiple times)"))
+        .subcommand(build_rules_subcommand())
+        .subcommand(SubCommand::with_name("manifest")
+            .about("Print the SHA-256 hashes of all files in a directory, to be used with --allowlist")
+            .arg(Arg::with_name("manifest-directory")
+                .help("Hash the files in this directory")
+                .takes_value(true)
+                .required(true)
+                .index(1)))
         ;
 
-    #[cfg(any(feature = "json", feature = "yaml"))]
     let app = app.arg(
         Arg::with_name("configuration")
-            .help("File with additional rules")
+            .help("File or directory with additional rules (can be given multiple times)")
             .short("c")
             .long("configuration")
-            .takes_value(true),
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .global(true),
     );
 
-    let matches = app.get_matches();
+    let app_matches = app.get_matches();
+    // Global arguments are propagated to the matches of the innermost subcommand
+    let matches = get_innermost_matches(&app_matches);
+
+    configure_logging(matches).unwrap();
+
+    let with_builtin = !matches.is_present("no-builtin");
+    let rule_pack_paths = matches
+        .values_of("rule-pack")
+        .map_or_else(Vec::new, |values| values.map(Path::new).collect::<Vec<_>>());
+    let configuration_paths = matches
+        .values_of("configuration")
+        .map_or_else(Vec::new, |values| values.map(Path::new).collect::<Vec<_>>());
+
+    if let ("rules", Some(rules_matches)) = app_matches.subcommand() {
+        if rules_matches.subcommand_name() == Some("packs") {
+            ui::print_rule_packs(&get_builtin_rule_packs(&rule_pack_paths)?);
+            return Ok(0);
+        }
+    }
+    let builtin_rules = if with_builtin {
+        Some(get_builtin_rules_from_packs(&rule_pack_paths)?)
+    } else {
+        None
+    };
+
+    // The check has to report invalid rule files instead of failing to load them
+    if let ("rules", Some(rules_matches)) = app_matches.subcommand() {
+        if rules_matches.subcommand_name() == Some("check") {
+            return Ok(check(&configuration_paths, builtin_rules));
+        }
+    }
 
-    configure_logging(&matches).unwrap();
+    if let ("manifest", Some(manifest_matches)) = app_matches.subcommand() {
+        let directory = manifest_matches
+            .value_of("manifest-directory")
+            .unwrap_or_default();
+        print!("{}", build_hash_manifest(directory)?);
+
+        return Ok(0);
+    }
 
-    #[cfg(not(any(feature = "json", feature = "yaml")))]
-    let rules = get_merged_rules(None)?;
-    #[cfg(any(feature = "json", feature = "yaml"))]
-    let rules = get_merged_rules(match matches.value_of("configuration") {
-        Some(c) => Some(Path::new(c)),
-        None => None,
-    })?;
+    let mut rules = apply_read_limit(
+        matches,
+        get_merged_rules(&configuration_paths, builtin_rules)?,
+    )?;
+    if rules.is_empty() {
+        bail!("No rules defined: Use --configuration to load rules if --no-builtin is given");
+    }
+    if let Some(allowlists) = matches.values_of("allowlist") {
+        let files: Vec<&str> = allowlists.collect();
+        let allowlist_rule =
+            Rule::with_path("known-good", Severity::WHITELIST, RawPath::with_path(""))?
+                .with_hash_files(&files)?;
+        rules.push(allowlist_rule);
+    }
+
+    if let ("rules", Some(rules_matches)) = app_matches.subcommand() {
+        return rules_command(rules_matches, rules);
+    }
 
     match matches.value_of("validate") {
-        Some(test_path) => validate(&matches, rules, test_path),
-        None => scan(&matches, rules),
+        Some(test_path) => validate(matches, rules, test_path).map(|_| 0),
+        None => scan(matches, rules),
+    }
+}
+
+fn build_rules_subcommand<'a, 'b>() -> App<'a, 'b> {
+    let subcommand = SubCommand::with_name("rules")
+        .about("Inspect the effective rule set (custom rules merged with the builtin rules)")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("list")
+                .about("List the rules with severity, path, content pattern and source file"),
+        )
+        .subcommand(SubCommand::with_name("check").about(
+            "Check the rule files for invalid patterns, duplicates and rules that can never match",
+        ))
+        .subcommand(
+            SubCommand::with_name("test")
+                .about("Check that the rules match the samples defined in their examples"),
+        )
+        .subcommand(
+            SubCommand::with_name("packs")
+                .about("List the builtin rule packs with version and source (see --rule-pack)"),
+        );
+
+    let subcommand = subcommand.subcommand(
+        SubCommand::with_name("export")
+            .about("Print the rules in the rule file format")
+            .arg(
+                Arg::with_name("export-format")
+                    .short("f")
+                    .long("format")
+                    .takes_value(true)
+                    .possible_values(ExportFormat::names())
+                    .default_value(ExportFormat::names()[0])
+                    .help("Sets the file format of the exported rules"),
+            ),
+    );
+
+    subcommand
+}
+
+fn get_innermost_matches<'a>(matches: &'a ArgMatches<'a>) -> &'a ArgMatches<'a> {
+    match matches.subcommand() {
+        (_, Some(subcommand_matches)) => get_innermost_matches(subcommand_matches),
+        _ => matches,
+    }
+}
+
+fn rules_command(matches: &ArgMatches, rules: Vec<Rule>) -> Result<i32, Error> {
+    match matches.subcommand() {
+        ("list", _) => ui::print_rules(&rules),
+        ("test", Some(test_matches)) => {
+            let results = classifier::run_rule_examples(&rules);
+            ui::print_example_results(&results, test_matches.occurrences_of("v") > 0);
+            if results.iter().any(|r| !r.passed()) {
+                return Ok(EXIT_CODE_ERROR);
+            }
+        }
+        ("export", Some(export_matches)) => {
+            let format: ExportFormat = match export_matches.value_of("export-format") {
+                Some(f) => f.parse()?,
+                None => bail!("No export format given"),
+            };
+            print!("{}", export_rules(&rules, format)?);
+        }
+        (name, _) => bail!("Unknown rules subcommand '{}'", name),
+    }
+
+    Ok(0)
+}
+
+/// Check the rules and return `EXIT_CODE_ERROR` if any error was found
+fn check(configuration_paths: &[&Path], builtin_rules: Option<Vec<Rule>>) -> i32 {
+    let issues = check_rules_with_builtin(configuration_paths, builtin_rules);
+    ui::print_lint_issues(&issues);
+
+    if issues.iter().any(|i| i.level == LintLevel::Error) {
+        EXIT_CODE_ERROR
+    } else {
+        0
     }
 }
 
@@ -98,21 +311,90 @@ fn get_verbosity_help() -> &'static str {
     "Sets the level of verbosity (-v = Info, -vv = Debug)"
 }
 
-fn scan(matches: &ArgMatches, rules: Vec<Rule>) -> Result<(), Error> {
+fn scan(matches: &ArgMatches, rules: Vec<Rule>) -> Result<i32, Error> {
     let min_severity = get_minimum_severity(matches);
     let root = get_root(matches);
     let quiet = matches.is_present("quiet");
+    let format = get_format(matches)?;
+    let jobs = get_jobs(matches)?;
+    let stream = matches.is_present("stream");
+    let verbose = matches.occurrences_of("v") > 0;
+    if stream && format != report::Format::Text {
+        bail!("Option --stream is only supported for the text format");
+    }
+    // The JUnit report lists every rated file, the other formats only the ones above min-severity
+    let keep_all_ratings = format == report::Format::Junit;
 
-    let files = file_finder::find_files(root, &rules);
+    let files = file_finder::find_files_iter(&root, &rules);
 
-    let ratings = sort_ratings(&rating::rate_entries(&files, &rules));
-    let summary = Summary::build(&ratings);
-    if !quiet || 0 < summary.ratings_above(min_severity) {
+    let mut summary = Summary::default();
+    let mut ratings = vec![];
+    rating::rate_entries_streaming(files, &rules, jobs, |rating| {
+        summary.add(&rating);
+        if stream {
+            if rating.rating() >= min_severity as isize {
+                ui::print_rating(&rating, verbose);
+            }
+        } else if keep_all_ratings || rating.rating() >= min_severity as isize {
+            ratings.push(rating);
+        }
+    });
+    let exit_code = get_exit_code(matches, &summary)?;
+    // Structured reports are written even if empty, so consumers can always parse the output
+    if quiet && format == report::Format::Text && 0 == summary.ratings_above(min_severity) {
+        return Ok(exit_code);
+    }
+    if stream {
+        println!();
         ui::print_summary(min_severity, &summary);
-        ui::print_ratings(min_severity, &ratings);
+
+        return Ok(exit_code);
     }
+    sort_ratings_in_place(&mut ratings);
 
-    Ok(())
+    match format {
+        report::Format::Text => {
+            ui::print_summary(min_severity, &summary);
+            ui::print_ratings(min_severity, &ratings, verbose);
+        }
+        #[cfg(feature = "json")]
+        report::Format::Json => ui::print_json(min_severity, &summary, &ratings)?,
+        #[cfg(feature = "json")]
+        report::Format::Sarif => ui::print_sarif(min_severity, &rules, &ratings)?,
+        report::Format::Junit => ui::print_junit(min_severity, &ratings),
+    }
+
+    Ok(exit_code)
+}
+
+/// Return the exit code for the highest severity found, if it is equal to or above `--fail-on`
+///
+/// | Highest severity | Exit code |
+/// |------------------|-----------|
+/// | none             | 0         |
+/// | NOTICE           | 2         |
+/// | MINOR            | 3         |
+/// | MAJOR            | 4         |
+/// | CRITICAL         | 5         |
+///
+/// Exit code 1 is reserved for internal errors and invalid command line arguments (see
+/// `EXIT_CODE_ERROR`)
+fn get_exit_code(matches: &ArgMatches<'_>, summary: &Summary) -> Result<i32, Error> {
+    let fail_on: Severity = match matches.value_of("fail-on") {
+        Some(s) => s.parse()?,
+        None => return Ok(0),
+    };
+    if summary.ratings_above(fail_on) == 0 {
+        return Ok(0);
+    }
+
+    Ok(match summary.highest_severity() {
+        Severity::CRITICAL => 5,
+        Severity::MAJOR => 4,
+        Severity::MINOR => 3,
+        Severity::NOTICE => 2,
+        _ => 0,
+    })
 }
 
 fn validate(matches: &ArgMatches, rules: Vec<Rule>, test_path: &str) -> Result<(), Error> {
@@ -149,11 +431,64 @@ fn get_minimum_severity(matches: &ArgMatches<'_>) -> Severity {
     }
 }
 
-/// Read the `Rule`s from the given path and merge them with the builtin rules
-fn get_merged_rules(path: Option<&Path>) -> Result<Vec<Rule>, Error> {
-    match path {
-        Some(p) => hackscanner_lib::get_merged_rules(p),
-        None => Ok(hackscanner_lib::get_builtin_rules()),
+fn get_jobs(matches: &ArgMatches<'_>) -> Result<usize, Error> {
+    match matches.value_of("jobs") {
+        Some(j) => match j.parse::<usize>() {
+            Ok(jobs) if jobs > 0 => Ok(jobs),
+            _ => bail!("Invalid number of jobs '{}'", j),
+        },
+        None => Ok(thread::available_parallelism().map_or(1, |n| n.get())),
+    }
+}
+
+/// Apply the read limit given on the command line to the rules (if any option is given)
+///
+/// The read limits defined by the rules themselves take precedence
+fn apply_read_limit(matches: &ArgMatches<'_>, rules: Vec<Rule>) -> Result<Vec<Rule>, Error> {
+    let mut read_limit = ReadLimit::default();
+    let mut changed = false;
+    if let Some(head) = matches.value_of("read-limit") {
+        read_limit.head = parse_byte_size(head)?;
+        changed = true;
+    }
+    if let Some(tail) = matches.value_of("read-tail") {
+        read_limit.tail = parse_byte_size(tail)?;
+        changed = true;
+    }
+    if matches.is_present("chunked") {
+        read_limit.chunked = true;
+        changed = true;
+    }
+    if let Some(overlap) = matches.value_of("chunk-overlap") {
+        read_limit.overlap = parse_byte_size(overlap)?;
+        changed = true;
+    }
+    if !changed {
+        return Ok(rules);
+    }
+    read_limit.validate()?;
+
+    rules
+        .into_iter()
+        .map(|rule| rule.with_default_read_limit(read_limit))
+        .collect()
+}
+
+fn get_format(matches: &ArgMatches<'_>) -> Result<report::Format, Error> {
+    match matches.value_of("format") {
+        Some(f) => f.parse(),
+        None => Ok(report::Format::Text),
+    }
+}
+
+/// Read the `Rule`s from the given paths and merge them with the builtin rules (if enabled)
+fn get_merged_rules(paths: &[&Path], builtin_rules: Option<Vec<Rule>>) -> Result<Vec<Rule>, Error> {
+    match (paths.is_empty(), builtin_rules) {
+        (true, Some(builtin_rules)) => Ok(builtin_rules),
+        (true, None) => Ok(vec![]),
+        (false, builtin_rules) => {
+            hackscanner_lib::get_rules_from_paths_with_builtin(paths, builtin_rules)
+        }
     }
 }
 
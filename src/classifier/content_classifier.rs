@@ -2,30 +2,96 @@ pub use self::error::ContentClassificationError;
 pub use self::error::ContentClassificationErrorKind;
 use super::Classification;
 use super::ClassifierTrait;
+use super::ContentMatch;
+use super::PartialScan;
+use super::TextPosition;
 use super::Violation;
 use crate::errors::Result;
-use crate::matcher::Matcher;
+use crate::matcher::{ContentScan, Matcher};
 use crate::rule::*;
 use crate::DirEntryTrait;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
 
 mod error;
 
-/// Number of bytes to read from files
-const BUFFER_SIZE: usize = 1024 * 1024 * 4;
+/// Part of the file content starting at `offset`
+struct ContentWindow {
+    offset: u64,
+    /// Lines and columns before the window (`None` if the content before it was not read)
+    position: Option<TextPosition>,
+    content: Vec<u8>,
+}
+
+impl ContentWindow {
+    /// Match the window with the `scan`, which stores the locations relative to the file start
+    fn scan(&self, scan: &mut ContentScan) {
+        scan.scan(&self.content, self.offset as usize, self.position);
+    }
+}
 
 pub struct ContentClassifier {
-    file_content_cache: String,
+    /// Content that is used instead of reading the file
+    fixed_content: Option<ContentWindow>,
+    /// Parts of the file content read for the read limits of the Rules
+    file_content_cache: HashMap<ReadLimit, Vec<ContentWindow>>,
+    /// Rules with chunked read limits that are matched in the same pass over the file
+    chunked_rules: Vec<Rule>,
+    /// Results of the chunked passes by read limit and content pattern
+    chunked_cache: HashMap<ReadLimit, HashMap<String, Option<Vec<ContentMatch>>>>,
+    partial_scan: Option<PartialScan>,
     path: PathBuf,
 }
 
 impl ContentClassifier {
-    fn get_file_content<D: DirEntryTrait>(
+    /// Create a classifier that uses the given `content` instead of reading the entry's file
+    pub(crate) fn with_content<D: DirEntryTrait, C: Into<Vec<u8>>>(entry: &D, content: C) -> Self {
+        ContentClassifier {
+            fixed_content: Some(ContentWindow {
+                offset: 0,
+                position: Some(TextPosition::default()),
+                content: content.into(),
+            }),
+            file_content_cache: HashMap::new(),
+            chunked_rules: vec![],
+            chunked_cache: HashMap::new(),
+            partial_scan: None,
+            path: entry.path().to_owned(),
+        }
+    }
+
+    /// Register the chunked content Rules that may be applied to the entry
+    ///
+    /// The file is streamed once per chunked read limit and the content of all registered Rules
+    /// with this read limit is matched on each chunk
+    pub(crate) fn with_chunked_rules<D: DirEntryTrait>(
+        mut self,
+        entry: &D,
+        rules: &[Rule],
+    ) -> Self {
+        self.chunked_rules = rules
+            .iter()
+            .filter(|rule| rule.has_content() && rule.read_limit().chunked)
+            .filter(|rule| Matcher::match_entry_path(*rule, entry))
+            .cloned()
+            .collect();
+
+        self
+    }
+
+    /// Return the smallest part of the file that was scanned, if it did not cover the whole file
+    pub(crate) fn partial_scan(&self) -> Option<PartialScan> {
+        self.partial_scan
+    }
+
+    fn find_entry_content<D: DirEntryTrait>(
         &mut self,
         entry: &D,
-    ) -> Result<&str, ContentClassificationError> {
+        rule: &Rule,
+    ) -> Result<Option<Vec<ContentMatch>>, ContentClassificationError> {
         if entry.path() != self.path.as_path() {
             unreachable!(
                 "Entry path does not match path stored in struct ContentClassifier. \n{:?} != \n{:?}",
@@ -33,61 +99,242 @@ impl ContentClassifier {
                 self.path
             );
         }
-        if self.file_content_cache.is_empty() {
-            self.read_file_content(entry)?;
+        let mut scan = match rule.content() {
+            Some(content_pattern) => ContentScan::new(content_pattern),
+            None => return Ok(None),
+        };
+        trace!(
+            "Match rule '{}' with pattern '{}'",
+            rule.name(),
+            scan.pattern()
+        );
+        if let Some(ref content) = self.fixed_content {
+            content.scan(&mut scan);
+
+            return Ok(scan.finish());
+        }
+
+        let read_limit = rule.read_limit();
+        if read_limit.chunked {
+            return self.find_chunked(scan, read_limit);
+        }
+        if !self.file_content_cache.contains_key(&read_limit) {
+            let windows = self.read_windows(read_limit)?;
+            self.file_content_cache.insert(read_limit, windows);
+        }
+        // The patterns of a content group may match in the head and the tail
+        for window in &self.file_content_cache[&read_limit] {
+            window.scan(&mut scan);
         }
 
-        Ok(self.file_content_cache.as_str())
+        Ok(scan.finish())
     }
 
-    fn read_file_content<D: DirEntryTrait>(
+    /// Read the head and tail of the file as defined by the `read_limit`
+    fn read_windows(
         &mut self,
-        entry: &D,
-    ) -> Result<(), ContentClassificationError> {
-        let path = entry.path();
-        let file = match File::open(path) {
-            Ok(f) => f,
-            Err(e) => return Err(ContentClassificationError::from_io_error(path, e)),
-        };
+        read_limit: ReadLimit,
+    ) -> Result<Vec<ContentWindow>, ContentClassificationError> {
+        let path = self.path.clone();
+        let mut file = open_file(&path)?;
+        let file_size = file
+            .metadata()
+            .map_err(|e| ContentClassificationError::from_io_error(&path, e))?
+            .len();
 
-        trace!("Will read file {}", path.display());
-        let mut buffer = Vec::with_capacity(BUFFER_SIZE);
+        trace!("Will read file {} ({})", path.display(), read_limit);
+        let mut head = Vec::new();
+        read_up_to(&path, &mut file, read_limit.head, &mut head)?;
+        let mut windows = vec![ContentWindow {
+            offset: 0,
+            position: Some(TextPosition::default()),
+            content: head,
+        }];
 
-        match file.take(BUFFER_SIZE as u64).read_to_end(&mut buffer) {
-            Ok(_bytes_count) => {}
-            Err(e) => {
-                trace!("Could not read file '{}': {}", path.display(), e);
-                return Err(ContentClassificationError::from_io_error(path, e));
+        let head_end = windows[0].content.len() as u64;
+        if read_limit.tail > 0 && file_size > head_end {
+            let tail_start = file_size.saturating_sub(read_limit.tail).max(head_end);
+            if tail_start == head_end {
+                // The tail directly follows the head
+                let head = &mut windows[0].content;
+                read_up_to(&path, &mut file, read_limit.tail, head)?;
+            } else {
+                let mut tail = Vec::new();
+                file.seek(SeekFrom::Start(tail_start))
+                    .map_err(|e| ContentClassificationError::from_io_error(&path, e))?;
+                read_up_to(&path, &mut file, read_limit.tail, &mut tail)?;
+                windows.push(ContentWindow {
+                    offset: tail_start,
+                    position: None,
+                    content: tail,
+                });
             }
-        };
+        }
         trace!("Did read file {}", path.display());
 
-        self.file_content_cache = String::from_utf8_lossy(&buffer).to_string();
+        let scanned_bytes: u64 = windows.iter().map(|w| w.content.len() as u64).sum();
+        if scanned_bytes < file_size {
+            debug!(
+                "File {} was only partially scanned ({} of {} bytes)",
+                path.display(),
+                scanned_bytes,
+                file_size
+            );
+            self.partial_scan = match self.partial_scan {
+                Some(partial_scan) if partial_scan.scanned_bytes() <= scanned_bytes => {
+                    Some(partial_scan)
+                }
+                _ => Some(PartialScan::new(scanned_bytes, file_size)),
+            };
+        }
+
+        Ok(windows)
+    }
+
+    /// Return the matches of the `scan`'s pattern from the chunked pass for the `read_limit`
+    ///
+    /// The first request for a read limit streams the file for the patterns of all registered
+    /// chunked Rules with this read limit
+    fn find_chunked(
+        &mut self,
+        scan: ContentScan,
+        read_limit: ReadLimit,
+    ) -> Result<Option<Vec<ContentMatch>>, ContentClassificationError> {
+        let key = scan.pattern().to_string();
+        if let Some(matches) = self
+            .chunked_cache
+            .get(&read_limit)
+            .and_then(|results| results.get(&key))
+        {
+            return Ok(matches.clone());
+        }
+
+        let mut scans: Vec<(String, ContentScan)> = vec![];
+        if !self.chunked_cache.contains_key(&read_limit) {
+            for rule in &self.chunked_rules {
+                if rule.read_limit() != read_limit {
+                    continue;
+                }
+                if let Some(content_pattern) = rule.content() {
+                    let rule_key = content_pattern.to_string();
+                    if rule_key != key && !scans.iter().any(|(k, _)| *k == rule_key) {
+                        scans.push((rule_key, ContentScan::new(content_pattern)));
+                    }
+                }
+            }
+        }
+        scans.push((key.clone(), scan));
+
+        self.find_in_chunks(&mut scans, read_limit)?;
+        let results = self.chunked_cache.entry(read_limit).or_default();
+        for (rule_key, scan) in scans {
+            results.insert(rule_key, scan.finish());
+        }
+
+        Ok(results[&key].clone())
+    }
+
+    /// Stream the whole file in overlapping chunks and match each chunk with the `scans` that
+    /// are not settled yet
+    ///
+    /// The patterns of a content group may match in different chunks
+    fn find_in_chunks(
+        &self,
+        scans: &mut [(String, ContentScan)],
+        read_limit: ReadLimit,
+    ) -> Result<(), ContentClassificationError> {
+        let path = self.path.as_path();
+        let mut file = open_file(path)?;
+        let chunk_size = read_limit.head;
+        let overlap = read_limit.overlap as usize;
+
+        trace!("Will read file {} in {}", path.display(), read_limit);
+        let mut window = ContentWindow {
+            offset: 0,
+            position: Some(TextPosition::default()),
+            content: Vec::with_capacity(chunk_size as usize),
+        };
+        loop {
+            let kept_bytes = window.content.len();
+            let missing_bytes = chunk_size - kept_bytes as u64;
+            let read_bytes = read_up_to(path, &mut file, missing_bytes, &mut window.content)?;
+            if read_bytes == 0 && kept_bytes > 0 {
+                // The kept overlap has already been matched as part of the previous chunk
+                return Ok(());
+            }
+            let mut settled = true;
+            for (_, scan) in scans.iter_mut().filter(|(_, scan)| !scan.is_settled()) {
+                window.scan(scan);
+                settled &= scan.is_settled();
+            }
+            if settled {
+                return Ok(());
+            }
+            if (read_bytes as u64) < missing_bytes {
+                trace!("Did read file {}", path.display());
+                return Ok(());
+            }
+
+            // Keep a character that is split at the end of the drained bytes in the window
+            let drained_bytes =
+                floor_char_boundary(&window.content, window.content.len() - overlap).max(1);
+            let drained = &window.content[..drained_bytes];
+            window.position = window.position.map(|position| position.advanced(drained));
+            window.offset += drained_bytes as u64;
+            window.content.drain(..drained_bytes);
+        }
+    }
+}
 
-        Ok(())
+/// Return the start of the UTF-8 character that contains the byte at `index`
+fn floor_char_boundary(content: &[u8], mut index: usize) -> usize {
+    let is_continuation = |b: &u8| b & 0b1100_0000 == 0b1000_0000;
+    while index > 0 && content.get(index).is_some_and(is_continuation) {
+        index -= 1;
     }
+    index
 }
 
-impl<'a, D: DirEntryTrait> ClassifierTrait<D> for ContentClassifier {
+pub(super) fn open_file(path: &Path) -> Result<File, ContentClassificationError> {
+    File::open(path).map_err(|e| ContentClassificationError::from_io_error(path, e))
+}
+
+/// Append up to `limit` bytes from `file` to the `buffer` and return the number of read bytes
+pub(super) fn read_up_to(
+    path: &Path,
+    file: &mut File,
+    limit: u64,
+    buffer: &mut Vec<u8>,
+) -> Result<usize, ContentClassificationError> {
+    file.take(limit).read_to_end(buffer).map_err(|e| {
+        trace!("Could not read file '{}': {}", path.display(), e);
+        ContentClassificationError::from_io_error(path, e)
+    })
+}
+
+impl<D: DirEntryTrait> ClassifierTrait<D> for ContentClassifier {
     fn new(entry: &D) -> Self {
         ContentClassifier {
-            file_content_cache: "".to_owned(),
+            fixed_content: None,
+            file_content_cache: HashMap::new(),
+            chunked_rules: vec![],
+            chunked_cache: HashMap::new(),
+            partial_scan: None,
             path: entry.path().to_owned(),
         }
     }
 
     fn classify(&mut self, entry: &D, rule: &Rule) -> Classification {
-        match self.get_file_content(entry) {
-            Ok(s) => {
-                if Matcher::match_entry_content(rule, s) {
-                    trace!("Rule's content does match");
+        match self.find_entry_content(entry, rule) {
+            Ok(Some(matches)) => {
+                trace!("Rule's content does match");
 
-                    Classification::Match(Violation::from(rule))
-                } else {
-                    trace!("Rule's content does not match");
+                Classification::Match(Violation::with_rule_and_matches(rule.clone(), matches))
+            }
+            Ok(None) => {
+                trace!("Rule's content does not match");
 
-                    Classification::NoMatch
-                }
+                Classification::NoMatch
             }
             // If the file content could not be read build a Violation from the error
             Err(e) => {
@@ -100,3 +347,290 @@ impl<'a, D: DirEntryTrait> ClassifierTrait<D> for ContentClassifier {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::severity::Severity;
+    use crate::test_helpers::TempDir;
+    use crate::StandaloneDirEntry;
+    use std::fs;
+
+    /// Classify a temporary file with the given `content` and return the first match
+    fn classify_content(
+        name: &str,
+        content: &[u8],
+        read_limit: RawReadLimit,
+    ) -> (Option<ContentMatch>, Option<PartialScan>) {
+        let rule = Rule::new(
+            "needle",
+            Severity::MAJOR,
+            RawPath::with_path(".php"),
+            Some("needle".to_string()),
+        )
+        .unwrap()
+        .with_read_limit(read_limit)
+        .unwrap();
+
+        classify_content_with_rule(name, content, &rule)
+    }
+
+    fn classify_content_with_rule(
+        name: &str,
+        content: &[u8],
+        rule: &Rule,
+    ) -> (Option<ContentMatch>, Option<PartialScan>) {
+        let dir = TempDir::new(&format!("read-limit-test-{}", name));
+        let entry = StandaloneDirEntry::from_path(dir.file("test.php", content)).unwrap();
+
+        let mut classifier: ContentClassifier = ClassifierTrait::new(&entry);
+        let classification = ClassifierTrait::classify(&mut classifier, &entry, rule);
+
+        let content_match = match classification {
+            Classification::Match(violation) => Some(violation.matches()[0].clone()),
+            Classification::NoMatch => None,
+            Classification::Error(violation) => panic!("Unexpected error {}", violation.name()),
+        };
+
+        (content_match, classifier.partial_scan())
+    }
+
+    fn content_with_needle_at(offset: usize) -> Vec<u8> {
+        let mut content = b"line\n".repeat(offset / 5);
+        content.resize(offset, b'x');
+        content.extend_from_slice(b"needle\nend\n");
+        content
+    }
+
+    #[test]
+    fn classify_head_test() {
+        let content = content_with_needle_at(100);
+        let read_limit = RawReadLimit {
+            head: Some(64),
+            ..RawReadLimit::default()
+        };
+
+        let (content_match, partial_scan) = classify_content("head", &content, read_limit);
+
+        assert_eq!(None, content_match);
+        assert_eq!(Some(PartialScan::new(64, 111)), partial_scan);
+    }
+
+    #[test]
+    fn classify_head_and_tail_test() {
+        let content = content_with_needle_at(100);
+        let read_limit = RawReadLimit {
+            head: Some(64),
+            tail: Some(16),
+            ..RawReadLimit::default()
+        };
+
+        let (content_match, partial_scan) = classify_content("tail", &content, read_limit);
+
+        let content_match = content_match.expect("The needle must be found in the tail");
+        assert_eq!(100, content_match.offset());
+        assert_eq!(None, content_match.line());
+        assert_eq!(None, content_match.column());
+        assert_eq!(Some(PartialScan::new(80, 111)), partial_scan);
+    }
+
+    #[test]
+    fn classify_head_and_tail_covering_file_test() {
+        let content = content_with_needle_at(100);
+        let read_limit = RawReadLimit {
+            head: Some(64),
+            tail: Some(64),
+            ..RawReadLimit::default()
+        };
+
+        let (content_match, partial_scan) = classify_content("covering", &content, read_limit);
+
+        let content_match = content_match.expect("The needle must be found");
+        assert_eq!(100, content_match.offset());
+        assert_eq!(Some(21), content_match.line());
+        assert_eq!(None, partial_scan);
+    }
+
+    #[test]
+    fn classify_chunked_test() {
+        let content = content_with_needle_at(100);
+        let read_limit = RawReadLimit {
+            head: Some(32),
+            chunked: Some(true),
+            overlap: Some(8),
+            ..RawReadLimit::default()
+        };
+
+        let (content_match, partial_scan) = classify_content("chunked", &content, read_limit);
+
+        let content_match = content_match.expect("The needle must be found in the last chunk");
+        assert_eq!(100, content_match.offset());
+        assert_eq!(Some(21), content_match.line());
+        assert_eq!(Some(1), content_match.column());
+        assert_eq!(None, partial_scan);
+    }
+
+    #[test]
+    fn classify_chunked_overlap_test() {
+        // The needle spans the border of the first and the second chunk
+        let content = content_with_needle_at(29);
+        let without_overlap = RawReadLimit {
+            head: Some(32),
+            chunked: Some(true),
+            overlap: Some(0),
+            ..RawReadLimit::default()
+        };
+        let with_overlap = RawReadLimit {
+            overlap: Some(8),
+            ..without_overlap
+        };
+
+        let (content_match, _) = classify_content("no-overlap", &content, without_overlap);
+        assert_eq!(None, content_match);
+
+        let (content_match, _) = classify_content("overlap", &content, with_overlap);
+        assert_eq!(
+            29,
+            content_match.expect("The needle must be found").offset()
+        );
+    }
+
+    #[test]
+    fn classify_chunked_mid_line_test() {
+        // The chunks after the first one start in the middle of the needle's line
+        let read_limit = RawReadLimit {
+            head: Some(32),
+            chunked: Some(true),
+            overlap: Some(8),
+            ..RawReadLimit::default()
+        };
+        let mut content = b"x".repeat(50);
+        content.extend_from_slice(b"needle\n");
+
+        let (content_match, _) = classify_content("mid-line", &content, read_limit);
+        let content_match = content_match.expect("The needle must be found");
+        assert_eq!(50, content_match.offset());
+        assert_eq!(Some(1), content_match.line());
+        assert_eq!(Some(51), content_match.column());
+
+        // The chunk borders split the multi-byte characters
+        let mut content = "<?php\nx".as_bytes().to_vec();
+        content.extend("ü".repeat(25).as_bytes());
+        content.extend_from_slice(b"needle\n");
+
+        let (content_match, _) = classify_content("mid-line-utf8", &content, read_limit);
+        let content_match = content_match.expect("The needle must be found");
+        assert_eq!(57, content_match.offset());
+        assert_eq!(Some(2), content_match.line());
+        assert_eq!(Some(27), content_match.column());
+    }
+
+    fn group_rule(group: RawContentGroup, read_limit: RawReadLimit) -> Rule {
+        Rule::with_path_and_content("group", Severity::MAJOR, RawPath::with_path(".php"), group)
+            .unwrap()
+            .with_read_limit(read_limit)
+            .unwrap()
+    }
+
+    #[test]
+    fn classify_group_none_in_head_test() {
+        let mut content = b"/* @license MIT */\n".to_vec();
+        content.extend(content_with_needle_at(100));
+        let read_limit = RawReadLimit {
+            head: Some(32),
+            tail: Some(16),
+            ..RawReadLimit::default()
+        };
+        let group = RawContentGroup::all(vec!["needle".into()]).with_none(vec!["@license".into()]);
+
+        let (content_match, _) = classify_content_with_rule(
+            "group-none-head",
+            &content,
+            &group_rule(group.clone(), read_limit),
+        );
+        assert_eq!(None, content_match);
+
+        let chunked = RawReadLimit {
+            chunked: Some(true),
+            overlap: Some(8),
+            ..read_limit
+        };
+        let (content_match, _) =
+            classify_content_with_rule("group-none-chunk", &content, &group_rule(group, chunked));
+        assert_eq!(None, content_match);
+    }
+
+    #[test]
+    fn classify_group_all_split_test() {
+        let mut content = b"<?php base64_decode($x);\n".to_vec();
+        content.extend(b"line\n".repeat(20));
+        content.extend_from_slice(b"eval($y);\n");
+        let read_limit = RawReadLimit {
+            head: Some(32),
+            tail: Some(16),
+            ..RawReadLimit::default()
+        };
+        let group = RawContentGroup::all(vec!["base64_decode".into(), "eval".into()]);
+
+        let (content_match, _) = classify_content_with_rule(
+            "group-all-split",
+            &content,
+            &group_rule(group.clone(), read_limit),
+        );
+        assert_eq!(
+            6,
+            content_match
+                .expect("The group must match across the head and the tail")
+                .offset()
+        );
+
+        let chunked = RawReadLimit {
+            chunked: Some(true),
+            overlap: Some(8),
+            ..read_limit
+        };
+        let (content_match, _) =
+            classify_content_with_rule("group-all-chunk", &content, &group_rule(group, chunked));
+        assert!(content_match.is_some());
+    }
+
+    #[test]
+    fn classify_chunked_rules_in_one_pass_test() {
+        let dir = TempDir::new("read-limit-test-one-pass");
+        let path = dir.file("test.php", content_with_needle_at(100));
+        let entry = StandaloneDirEntry::from_path(&path).unwrap();
+        let read_limit = RawReadLimit {
+            head: Some(32),
+            chunked: Some(true),
+            overlap: Some(8),
+            ..RawReadLimit::default()
+        };
+        let rule = |name: &str, pattern: &str| {
+            Rule::new(
+                name,
+                Severity::MAJOR,
+                RawPath::with_path(".php"),
+                Some(pattern.to_string()),
+            )
+            .unwrap()
+            .with_read_limit(read_limit)
+            .unwrap()
+        };
+        let rules = vec![rule("needle", "needle"), rule("end", "end")];
+
+        let mut classifier = <ContentClassifier as ClassifierTrait<_>>::new(&entry)
+            .with_chunked_rules(&entry, &rules);
+        let needle = ClassifierTrait::classify(&mut classifier, &entry, &rules[0]);
+        // The second Rule was matched in the same pass
+        let cached = &classifier.chunked_cache[&rules[1].read_limit()];
+        assert_eq!(2, cached.len());
+        fs::remove_file(&path).unwrap();
+        let end = ClassifierTrait::classify(&mut classifier, &entry, &rules[1]);
+
+        assert!(matches!(needle, Classification::Match(_)));
+        match end {
+            Classification::Match(violation) => assert_eq!(107, violation.matches()[0].offset()),
+            _ => panic!("The second Rule must match"),
+        }
+    }
+}
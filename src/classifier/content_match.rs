@@ -0,0 +1,222 @@
+use regex::bytes::{Captures, Regex};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Maximum number of characters of an excerpt
+const MAX_EXCERPT_LENGTH: usize = 80;
+
+/// Number of lines and of characters on the current line before a part of the file content
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TextPosition {
+    lines: usize,
+    columns: usize,
+}
+
+impl TextPosition {
+    /// Return the position after the `content` that directly follows this position
+    pub(crate) fn advanced(self, content: &[u8]) -> Self {
+        match content.iter().rposition(|b| *b == b'\n') {
+            Some(line_end) => TextPosition {
+                lines: self.lines + content.iter().filter(|b| **b == b'\n').count(),
+                columns: count_characters(&content[line_end + 1..]),
+            },
+            None => TextPosition {
+                lines: self.lines,
+                columns: self.columns + count_characters(content),
+            },
+        }
+    }
+}
+
+/// Location and excerpt of a content pattern match
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContentMatch {
+    offset: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+    excerpt: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    captures: BTreeMap<String, String>,
+}
+
+impl ContentMatch {
+    pub(crate) fn from_captures(content: &[u8], regex: &Regex, captures: &Captures<'_>) -> Self {
+        let whole_match = captures
+            .get(0)
+            .expect("Capture group 0 always exists for a match");
+        let offset = whole_match.start();
+        let before = &content[..offset];
+        let line = before.iter().filter(|b| **b == b'\n').count() + 1;
+        let line_start = before
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |position| position + 1);
+        let column = count_characters(&content[line_start..offset]) + 1;
+
+        let captures = regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| {
+                captures
+                    .name(name)
+                    .map(|group| (name.to_owned(), build_excerpt(group.as_bytes())))
+            })
+            .collect();
+
+        ContentMatch {
+            offset,
+            line: Some(line),
+            column: Some(column),
+            excerpt: build_excerpt(whole_match.as_bytes()),
+            captures,
+        }
+    }
+
+    /// Return the match moved by the position of the read part of the file content
+    ///
+    /// If the `position` of the read part is unknown, the line and column are removed
+    pub(crate) fn shifted(mut self, offset: usize, position: Option<TextPosition>) -> Self {
+        self.offset += offset;
+        match position {
+            Some(position) => {
+                // The first line of the read part may continue a line before it
+                if self.line == Some(1) {
+                    self.column = self.column.map(|column| column + position.columns);
+                }
+                self.line = self.line.map(|line| line + position.lines);
+            }
+            None => {
+                self.line = None;
+                self.column = None;
+            }
+        }
+        self
+    }
+
+    /// Return the byte offset of the match in the file
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Return the line number of the match (starting at 1)
+    ///
+    /// Matches in the separately read tail of a file have no line number, because the lines
+    /// before the tail are not read
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Return the column of the match in characters (starting at 1), if the line is known
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    /// Return the escaped and truncated matched text (bytes that are not valid UTF-8 are
+    /// escaped as `\xNN`)
+    pub fn excerpt(&self) -> &str {
+        &self.excerpt
+    }
+
+    /// Return the escaped and truncated texts of the named capture groups
+    pub fn captures(&self) -> &BTreeMap<String, String> {
+        &self.captures
+    }
+}
+
+impl fmt::Display for ContentMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                write!(f, "line {}, column {}: {}", line, column, self.excerpt)?
+            }
+            _ => write!(f, "offset {}: {}", self.offset, self.excerpt)?,
+        }
+        for (name, value) in &self.captures {
+            write!(f, " ({}: {})", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Return the number of characters in `input`, counting invalid UTF-8 as replacement characters
+fn count_characters(input: &[u8]) -> usize {
+    String::from_utf8_lossy(input).chars().count()
+}
+
+/// Escape control characters and invalid UTF-8 in `input` and truncate it to
+/// `MAX_EXCERPT_LENGTH` characters
+fn build_excerpt(input: &[u8]) -> String {
+    let mut excerpt = String::new();
+    let mut length = 0;
+    for chunk in input.utf8_chunks() {
+        let escaped_bytes = chunk.invalid().iter().map(|b| format!("\\x{:02x}", b));
+        let chars = chunk.valid().chars().map(|c| {
+            if c.is_control() {
+                c.escape_default().collect()
+            } else {
+                c.to_string()
+            }
+        });
+        for part in chars.chain(escaped_bytes) {
+            if length == MAX_EXCERPT_LENGTH {
+                excerpt.push('…');
+                return excerpt;
+            }
+            excerpt.push_str(&part);
+            length += 1;
+        }
+    }
+
+    excerpt
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_captures_test() {
+        let content = b"<?php\n  $a = 1;\n  eval($_POST['cmd']);\n";
+        let regex = Regex::new(r"\beval\((?P<argument>[^)]*)\)").unwrap();
+        let captures = regex.captures(content).unwrap();
+
+        let content_match = ContentMatch::from_captures(content, &regex, &captures);
+
+        assert_eq!(18, content_match.offset());
+        assert_eq!(Some(3), content_match.line());
+        assert_eq!(Some(3), content_match.column());
+        assert_eq!("eval($_POST['cmd'])", content_match.excerpt());
+        assert_eq!("$_POST['cmd']", content_match.captures()["argument"]);
+        assert_eq!(
+            "line 3, column 3: eval($_POST['cmd']) (argument: $_POST['cmd'])",
+            content_match.to_string()
+        );
+
+        let tail_match = content_match.shifted(100, None);
+        assert_eq!(118, tail_match.offset());
+        assert_eq!(None, tail_match.line());
+        assert_eq!(None, tail_match.column());
+        assert_eq!(
+            "offset 118: eval($_POST['cmd']) (argument: $_POST['cmd'])",
+            tail_match.to_string()
+        );
+    }
+
+    #[test]
+    fn build_excerpt_test() {
+        assert_eq!("a\\nb\\t", build_excerpt(b"a\nb\t"));
+        assert_eq!(
+            "\\u{7f}ELF\\xff€",
+            build_excerpt(b"\x7fELF\xff\xe2\x82\xac")
+        );
+        let long = "x".repeat(100);
+        assert_eq!(
+            format!("{}…", "x".repeat(MAX_EXCERPT_LENGTH)),
+            build_excerpt(long.as_bytes())
+        );
+    }
+}
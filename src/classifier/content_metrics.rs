@@ -0,0 +1,199 @@
+use crate::rule::{format_byte_size, Heuristics};
+use serde::Serialize;
+use std::fmt;
+
+/// Minimum number of consecutive `\xNN` escapes counted as hex encoded blob
+const MIN_ESCAPED_HEX_BYTES: usize = 2;
+
+/// Statistics of a file's content that indicate obfuscated code
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ContentMetrics {
+    entropy: f64,
+    longest_line: usize,
+    non_alphanumeric_ratio: f64,
+    blob_size: usize,
+}
+
+impl ContentMetrics {
+    pub fn from_content(content: &[u8]) -> Self {
+        ContentMetrics {
+            entropy: shannon_entropy(content),
+            longest_line: content
+                .split(|byte| *byte == b'\n')
+                .map(<[u8]>::len)
+                .max()
+                .unwrap_or_default(),
+            non_alphanumeric_ratio: non_alphanumeric_ratio(content),
+            blob_size: longest_base64_run(content).max(longest_escaped_hex_run(content)),
+        }
+    }
+
+    /// Return the Shannon entropy in bits per byte (0 to 8)
+    pub fn entropy(&self) -> f64 {
+        self.entropy
+    }
+
+    /// Return the length of the longest line in bytes
+    pub fn longest_line(&self) -> usize {
+        self.longest_line
+    }
+
+    /// Return the ratio of the non-alphanumeric characters to all non-whitespace characters
+    pub fn non_alphanumeric_ratio(&self) -> f64 {
+        self.non_alphanumeric_ratio
+    }
+
+    /// Return the size of the largest base64 or hex encoded blob in bytes
+    pub fn blob_size(&self) -> usize {
+        self.blob_size
+    }
+
+    /// Return if each metric reaches the threshold defined in `heuristics`
+    pub fn reaches(&self, heuristics: &Heuristics) -> bool {
+        !heuristics.is_empty()
+            && heuristics.min_entropy.is_none_or(|min| self.entropy >= min)
+            && heuristics
+                .min_longest_line
+                .is_none_or(|min| self.longest_line as u64 >= min)
+            && heuristics
+                .min_non_alphanumeric_ratio
+                .is_none_or(|min| self.non_alphanumeric_ratio >= min)
+            && heuristics
+                .min_blob_size
+                .is_none_or(|min| self.blob_size as u64 >= min)
+    }
+}
+
+impl fmt::Display for ContentMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "entropy {:.2}, longest line {}, non-alphanumeric {:.0}%, largest blob {}",
+            self.entropy,
+            format_byte_size(self.longest_line as u64),
+            self.non_alphanumeric_ratio * 100.0,
+            format_byte_size(self.blob_size as u64)
+        )
+    }
+}
+
+fn shannon_entropy(content: &[u8]) -> f64 {
+    if content.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for byte in content {
+        counts[*byte as usize] += 1;
+    }
+    let length = content.len() as f64;
+
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / length;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+fn non_alphanumeric_ratio(content: &[u8]) -> f64 {
+    let (non_alphanumeric, total) = content
+        .iter()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .fold((0usize, 0usize), |(non_alphanumeric, total), byte| {
+            if byte.is_ascii_alphanumeric() {
+                (non_alphanumeric, total + 1)
+            } else {
+                (non_alphanumeric + 1, total + 1)
+            }
+        });
+    if total == 0 {
+        0.0
+    } else {
+        non_alphanumeric as f64 / total as f64
+    }
+}
+
+/// Return the length of the longest run of base64 characters (hex digits are a subset)
+fn longest_base64_run(content: &[u8]) -> usize {
+    content
+        .split(|byte| !(byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'=')))
+        .map(<[u8]>::len)
+        .max()
+        .unwrap_or_default()
+}
+
+/// Return the length of the longest run of `\xNN` escapes in bytes
+fn longest_escaped_hex_run(content: &[u8]) -> usize {
+    let is_escape = |window: &[u8]| {
+        window[0] == b'\\'
+            && (window[1] == b'x' || window[1] == b'X')
+            && window[2].is_ascii_hexdigit()
+            && window[3].is_ascii_hexdigit()
+    };
+    let mut longest = 0;
+    let mut index = 0;
+    while index + 4 <= content.len() {
+        let start = index;
+        while index + 4 <= content.len() && is_escape(&content[index..index + 4]) {
+            index += 4;
+        }
+        if index > start {
+            if (index - start) / 4 >= MIN_ESCAPED_HEX_BYTES {
+                longest = longest.max(index - start);
+            }
+        } else {
+            index += 1;
+        }
+    }
+
+    longest
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_content_test() {
+        let metrics = ContentMetrics::from_content(b"aaaa\nabcd+/==\n\n");
+        assert_eq!(8, metrics.longest_line());
+        assert_eq!(8, metrics.blob_size());
+        assert_eq!(4.0 / 12.0, metrics.non_alphanumeric_ratio());
+
+        let empty = ContentMetrics::from_content(b"");
+        assert_eq!(0.0, empty.entropy());
+        assert_eq!(0, empty.longest_line());
+        assert_eq!(0.0, empty.non_alphanumeric_ratio());
+    }
+
+    #[test]
+    fn entropy_test() {
+        assert_eq!(0.0, ContentMetrics::from_content(b"aaaaaaaa").entropy());
+        assert_eq!(1.0, ContentMetrics::from_content(b"abababab").entropy());
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(8.0, ContentMetrics::from_content(&all_bytes).entropy());
+    }
+
+    #[test]
+    fn escaped_hex_blob_test() {
+        let metrics = ContentMetrics::from_content(br#"$f = "\x65\x76\x61\x6c"; $g = "\x41";"#);
+        assert_eq!(16, metrics.blob_size());
+    }
+
+    #[test]
+    fn reaches_test() {
+        let metrics = ContentMetrics::from_content(b"<?php $a = 'ZXZhbCgkX1BPU1RbJ2NtZCddKTs=';");
+        assert!(metrics.reaches(&Heuristics {
+            min_blob_size: Some(16),
+            ..Heuristics::default()
+        }));
+        assert!(!metrics.reaches(&Heuristics {
+            min_blob_size: Some(16),
+            min_longest_line: Some(1024),
+            ..Heuristics::default()
+        }));
+        assert!(!metrics.reaches(&Heuristics::default()));
+    }
+}
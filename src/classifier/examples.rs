@@ -0,0 +1,114 @@
+use super::classification::Classification;
+use super::classify_entry_with_rule;
+use super::content_classifier::ContentClassifier;
+use super::file_condition_classifier::FileConditionClassifier;
+use super::hash_classifier::HashClassifier;
+use super::heuristic_classifier::HeuristicClassifier;
+use super::path_classifier::PathClassifier;
+use super::ClassifierTrait;
+use crate::dir_entry::StandaloneDirEntry;
+use crate::fs::StandaloneFileType;
+use crate::rule::{Rule, RuleExample};
+
+/// Result of classifying one of the samples defined in a Rule's `examples`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleResult<'a> {
+    pub rule: &'a Rule,
+    pub example: &'a RuleExample,
+    /// The Rule has to match the example
+    pub expect_match: bool,
+    /// The Rule did match the example
+    pub did_match: bool,
+}
+
+impl ExampleResult<'_> {
+    pub fn passed(&self) -> bool {
+        self.expect_match == self.did_match
+    }
+}
+
+/// Classify the `examples` of each of the `rules` and return the results
+///
+/// The samples are not read from disk: the path is matched as given and the content snippet is used
+/// as the file's content. File conditions (size, permissions, ...) are considered fulfilled
+pub fn run_rule_examples(rules: &[Rule]) -> Vec<ExampleResult<'_>> {
+    rules
+        .iter()
+        .flat_map(|rule| {
+            let examples = rule.examples();
+            let matches = examples.matches.iter().map(move |e| (e, true));
+            let no_matches = examples.no_match.iter().map(move |e| (e, false));
+
+            matches
+                .chain(no_matches)
+                .map(move |(example, expect_match)| ExampleResult {
+                    rule,
+                    example,
+                    expect_match,
+                    did_match: matches_example(rule, example),
+                })
+        })
+        .collect()
+}
+
+fn matches_example(rule: &Rule, example: &RuleExample) -> bool {
+    let entry =
+        StandaloneDirEntry::from_path_with_file_type(&example.path, StandaloneFileType::File);
+    let mut path_classifier = <PathClassifier as ClassifierTrait<StandaloneDirEntry>>::new(&entry);
+    let content = example.content.as_deref().unwrap_or_default();
+    let mut file_condition_classifier = FileConditionClassifier::unchecked();
+    let mut hash_classifier = HashClassifier::with_content(&entry, content);
+    let mut heuristic_classifier = HeuristicClassifier::with_content(&entry, content.as_bytes());
+    let mut content_classifier = ContentClassifier::with_content(&entry, content);
+
+    matches!(
+        classify_entry_with_rule(
+            &mut path_classifier,
+            &mut file_condition_classifier,
+            &mut hash_classifier,
+            &mut heuristic_classifier,
+            &mut content_classifier,
+            &entry,
+            rule,
+        ),
+        Classification::Match(_)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rule::{RawPath, RuleExamples};
+    use crate::Severity;
+
+    #[test]
+    fn run_rule_examples_test() {
+        let rule = Rule::with_path_and_content(
+            "uploaded eval",
+            Severity::MAJOR,
+            RawPath::with_path("uploads/"),
+            r"eval\(",
+        )
+        .unwrap()
+        .with_examples(RuleExamples::new(
+            vec![
+                RuleExample::new("uploads/shell.php", Some("<?php eval($x);".to_owned())),
+                RuleExample::new("uploads/image.jpg", None),
+            ],
+            vec![
+                RuleExample::new("src/shell.php", Some("<?php eval($x);".to_owned())),
+                RuleExample::new("uploads/clean.php", Some("<?php echo 1;".to_owned())),
+            ],
+        ));
+        let rules = vec![rule];
+        let results = run_rule_examples(&rules);
+
+        assert_eq!(4, results.len());
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|r| !r.passed())
+            .map(|r| r.example.path.as_str())
+            .collect();
+        assert_eq!(vec!["uploads/image.jpg"], failed);
+    }
+}
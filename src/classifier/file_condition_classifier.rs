@@ -0,0 +1,78 @@
+use super::Classification;
+use super::ClassifierTrait;
+use super::ContentClassificationError;
+use super::Violation;
+use crate::rule::*;
+use crate::DirEntryTrait;
+use std::io;
+use std::time::SystemTime;
+
+/// Classifier checking the size, permissions, owner and timestamps of a file against the file
+/// conditions of a Rule
+///
+/// The metadata is only read once a Rule with file conditions is checked, and then reused for all
+/// following Rules
+pub struct FileConditionClassifier {
+    stat: Option<io::Result<FileStat>>,
+    now: SystemTime,
+    /// Consider all file conditions as fulfilled
+    unchecked: bool,
+}
+
+impl FileConditionClassifier {
+    /// Create a classifier that considers all file conditions as fulfilled
+    ///
+    /// Used for samples that do not exist on disk
+    pub(crate) fn unchecked() -> Self {
+        FileConditionClassifier {
+            stat: None,
+            now: SystemTime::now(),
+            unchecked: true,
+        }
+    }
+}
+
+impl<D: DirEntryTrait> ClassifierTrait<D> for FileConditionClassifier {
+    fn new(_entry: &D) -> Self {
+        FileConditionClassifier {
+            stat: None,
+            now: SystemTime::now(),
+            unchecked: false,
+        }
+    }
+
+    fn classify(&mut self, entry: &D, rule: &Rule) -> Classification {
+        let file_condition = match rule.file_condition() {
+            Some(file_condition) => file_condition,
+            None => return Classification::NoMatch,
+        };
+        if self.unchecked {
+            return Classification::Match(Violation::with_rule(rule.clone()));
+        }
+
+        let stat = self
+            .stat
+            .get_or_insert_with(|| entry.metadata().map(|m| FileStat::from_metadata(&m)));
+        match stat {
+            Ok(stat) if file_condition.matches(stat, self.now) => {
+                trace!("File fulfills the Rule's file condition");
+
+                Classification::Match(Violation::with_rule(rule.clone()))
+            }
+            Ok(_) => {
+                trace!("File does not fulfill the Rule's file condition");
+
+                Classification::NoMatch
+            }
+            // If the metadata could not be read build a Violation from the error
+            Err(e) => {
+                let e = io::Error::new(e.kind(), e.to_string());
+                let error = ContentClassificationError::from_io_error(entry.path(), e);
+                match Violation::with_rule_and_file_io_error(rule.clone(), &error) {
+                    Some(v) => Classification::Error(v),
+                    None => Classification::NoMatch,
+                }
+            }
+        }
+    }
+}
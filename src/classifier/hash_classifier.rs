@@ -0,0 +1,60 @@
+use super::Classification;
+use super::ClassifierTrait;
+use super::ContentClassificationError;
+use super::Violation;
+use crate::file_hash::FileHashes;
+use crate::rule::*;
+use crate::DirEntryTrait;
+
+/// Classifier matching the hash of the file content against the hash list of a Rule
+///
+/// The hashes are only computed once a Rule with a hash list is checked, and then reused for all
+/// following Rules
+pub struct HashClassifier {
+    hashes: FileHashes,
+}
+
+impl HashClassifier {
+    /// Create a classifier that hashes the given `content` instead of the entry's file
+    pub(crate) fn with_content<D: DirEntryTrait, C: Into<Vec<u8>>>(entry: &D, content: C) -> Self {
+        HashClassifier {
+            hashes: FileHashes::with_content(entry.path(), content),
+        }
+    }
+}
+
+impl<D: DirEntryTrait> ClassifierTrait<D> for HashClassifier {
+    fn new(entry: &D) -> Self {
+        HashClassifier {
+            hashes: FileHashes::new(entry.path()),
+        }
+    }
+
+    fn classify(&mut self, entry: &D, rule: &Rule) -> Classification {
+        let hash_list = match rule.hashes() {
+            Some(hash_list) => hash_list,
+            None => return Classification::NoMatch,
+        };
+
+        match self.hashes.is_contained_in(hash_list) {
+            Ok(true) => {
+                trace!("Rule's hash list contains the file's hash");
+
+                Classification::Match(Violation::with_rule(rule.clone()))
+            }
+            Ok(false) => {
+                trace!("Rule's hash list does not contain the file's hash");
+
+                Classification::NoMatch
+            }
+            // If the file could not be hashed build a Violation from the error
+            Err(e) => {
+                let error = ContentClassificationError::from_io_error(entry.path(), e);
+                match Violation::with_rule_and_file_io_error(rule.clone(), &error) {
+                    Some(v) => Classification::Error(v),
+                    None => Classification::NoMatch,
+                }
+            }
+        }
+    }
+}
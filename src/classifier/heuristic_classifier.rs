@@ -0,0 +1,92 @@
+use super::content_classifier::{open_file, read_up_to};
+use super::Classification;
+use super::ClassifierTrait;
+use super::ContentClassificationError;
+use super::ContentMetrics;
+use super::Violation;
+use crate::rule::*;
+use crate::DirEntryTrait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Classifier comparing the entropy, line length, character distribution and blob size of the
+/// file content with the heuristics of a Rule
+///
+/// The metrics are computed from the first `head` bytes of the Rule's read limit. They are only
+/// computed once a Rule with heuristics is checked, and then reused for all following Rules
+pub struct HeuristicClassifier {
+    /// Content that is used instead of reading the file
+    fixed_content: Option<ContentMetrics>,
+    metrics_cache: HashMap<u64, ContentMetrics>,
+    path: PathBuf,
+}
+
+impl HeuristicClassifier {
+    /// Create a classifier that computes the metrics of the given `content` instead of the
+    /// entry's file
+    pub(crate) fn with_content<D: DirEntryTrait>(entry: &D, content: &[u8]) -> Self {
+        HeuristicClassifier {
+            fixed_content: Some(ContentMetrics::from_content(content)),
+            metrics_cache: HashMap::new(),
+            path: entry.path().to_owned(),
+        }
+    }
+
+    fn metrics(&mut self, head: u64) -> Result<ContentMetrics, ContentClassificationError> {
+        if let Some(metrics) = self.fixed_content {
+            return Ok(metrics);
+        }
+        if let Some(metrics) = self.metrics_cache.get(&head) {
+            return Ok(*metrics);
+        }
+
+        let metrics = ContentMetrics::from_content(&read_head(&self.path, head)?);
+        self.metrics_cache.insert(head, metrics);
+
+        Ok(metrics)
+    }
+}
+
+fn read_head(path: &Path, head: u64) -> Result<Vec<u8>, ContentClassificationError> {
+    trace!("Will read {} bytes of file {}", head, path.display());
+    let mut file = open_file(path)?;
+    let mut buffer = vec![];
+    read_up_to(path, &mut file, head, &mut buffer)?;
+
+    Ok(buffer)
+}
+
+impl<D: DirEntryTrait> ClassifierTrait<D> for HeuristicClassifier {
+    fn new(entry: &D) -> Self {
+        HeuristicClassifier {
+            fixed_content: None,
+            metrics_cache: HashMap::new(),
+            path: entry.path().to_owned(),
+        }
+    }
+
+    fn classify(&mut self, _entry: &D, rule: &Rule) -> Classification {
+        let heuristics = match rule.heuristics() {
+            Some(heuristics) => heuristics,
+            None => return Classification::NoMatch,
+        };
+
+        match self.metrics(rule.read_limit().head) {
+            Ok(metrics) if metrics.reaches(heuristics) => {
+                trace!("Content metrics reach the Rule's heuristics");
+
+                Classification::Match(Violation::with_rule(rule.clone()).with_metrics(metrics))
+            }
+            Ok(_) => {
+                trace!("Content metrics do not reach the Rule's heuristics");
+
+                Classification::NoMatch
+            }
+            // If the file content could not be read build a Violation from the error
+            Err(e) => match Violation::with_rule_and_file_io_error(rule.clone(), &e) {
+                Some(v) => Classification::Error(v),
+                None => Classification::NoMatch,
+            },
+        }
+    }
+}
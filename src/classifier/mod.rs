@@ -1,14 +1,30 @@
 mod classification;
 mod content_classifier;
+mod content_match;
+mod content_metrics;
+mod examples;
+mod file_condition_classifier;
+mod hash_classifier;
+mod heuristic_classifier;
+mod partial_scan;
 mod path_classifier;
 mod violation;
 
 use crate::dir_entry::*;
 use crate::rule::*;
+use crate::severity::Severity;
 
 use self::classification::*;
 pub use self::content_classifier::ContentClassificationError;
 use self::content_classifier::ContentClassifier;
+pub use self::content_match::ContentMatch;
+pub(crate) use self::content_match::TextPosition;
+pub use self::content_metrics::ContentMetrics;
+pub use self::examples::{run_rule_examples, ExampleResult};
+use self::file_condition_classifier::FileConditionClassifier;
+use self::hash_classifier::HashClassifier;
+use self::heuristic_classifier::HeuristicClassifier;
+pub use self::partial_scan::PartialScan;
 use self::path_classifier::PathClassifier;
 pub use self::violation::Violation;
 
@@ -24,23 +40,51 @@ pub fn classify_entries<D: DirEntryTrait>(entries: &[D], rules: &[Rule]) -> Vec<
 }
 
 pub fn classify_entry<D: DirEntryTrait>(entry: &D, rules: &[Rule]) -> Vec<Violation> {
+    scan_entry(entry, rules).0
+}
+
+/// Classify the entry and return the Violations and if the file was only partially scanned
+///
+/// If the file's hash is contained in the hash list of a `WHITELIST` Rule, the other Rules are
+/// not applied
+pub(crate) fn scan_entry<D: DirEntryTrait>(
+    entry: &D,
+    rules: &[Rule],
+) -> (Vec<Violation>, Option<PartialScan>) {
     let mut path_classifier = path_classifier::PathClassifier::new(entry);
-    let mut content_classifier = content_classifier::ContentClassifier::new(entry);
-    rules
+    let mut file_condition_classifier = FileConditionClassifier::new(entry);
+    let mut hash_classifier = hash_classifier::HashClassifier::new(entry);
+    let mut heuristic_classifier = HeuristicClassifier::new(entry);
+    let mut content_classifier =
+        content_classifier::ContentClassifier::new(entry).with_chunked_rules(entry, rules);
+    let mut classify = |rule: &Rule| match classify_entry_with_rule(
+        &mut path_classifier,
+        &mut file_condition_classifier,
+        &mut hash_classifier,
+        &mut heuristic_classifier,
+        &mut content_classifier,
+        entry,
+        rule,
+    ) {
+        Classification::NoMatch => None,
+        Classification::Match(violation) => Some(violation),
+        Classification::Error(violation) => Some(violation),
+    };
+
+    let known_good: Vec<Violation> = rules
         .iter()
-        .filter_map(|rule| {
-            match classify_entry_with_rule(
-                &mut path_classifier,
-                &mut content_classifier,
-                entry,
-                rule,
-            ) {
-                Classification::NoMatch => None,
-                Classification::Match(violation) => Some(violation),
-                Classification::Error(violation) => Some(violation),
-            }
-        })
-        .collect()
+        .filter(|rule| rule.is_hash_allowlist())
+        .filter_map(&mut classify)
+        .filter(|violation| violation.severity() == Severity::WHITELIST)
+        .collect();
+    if !known_good.is_empty() {
+        debug!("Entry {:?} has a known-good hash", entry);
+        return (known_good, None);
+    }
+
+    let violations = rules.iter().filter_map(classify).collect();
+
+    (violations, content_classifier.partial_scan())
 }
 
 trait ClassifierTrait<D: DirEntryTrait> {
@@ -50,34 +94,68 @@ trait ClassifierTrait<D: DirEntryTrait> {
 
 fn classify_entry_with_rule<D: DirEntryTrait>(
     path_classifier: &mut PathClassifier,
+    file_condition_classifier: &mut FileConditionClassifier,
+    hash_classifier: &mut HashClassifier,
+    heuristic_classifier: &mut HeuristicClassifier,
     content_classifier: &mut ContentClassifier,
     entry: &D,
     rule: &Rule,
 ) -> Classification {
-    if rule.has_content() {
-        let path_classification = ClassifierTrait::classify(path_classifier, entry, rule);
-        match path_classification {
-            Classification::NoMatch => {
-                /* Path does not match. No need to check the content */
-                Classification::NoMatch
-            }
-            Classification::Match(_) => {
-                /* Path does match. Now check the content */
-                ClassifierTrait::classify(content_classifier, entry, rule)
-            }
-            Classification::Error(_) => {
-                unreachable!("Classification::Error is not implemented for `PathClassifier`")
-            }
+    let path_classification = ClassifierTrait::classify(path_classifier, entry, rule);
+    match path_classification {
+        Classification::NoMatch => {
+            /* Path does not match. No need to check the metadata, hash or content */
+            return Classification::NoMatch;
         }
-    } else {
-        let path_classification = ClassifierTrait::classify(path_classifier, entry, rule);
+        Classification::Match(_) => {}
+        Classification::Error(_) => {
+            unreachable!("Classification::Error is not implemented for `PathClassifier`")
+        }
+    }
+
+    if rule.has_file_condition() {
+        /* Path does match. Now check the file's metadata */
+        match ClassifierTrait::classify(file_condition_classifier, entry, rule) {
+            Classification::Match(_) => {}
+            file_classification => return file_classification,
+        }
+    }
+
+    if rule.has_hashes() {
+        /* Path (and metadata) does match. Now check the hash */
+        match ClassifierTrait::classify(hash_classifier, entry, rule) {
+            Classification::Match(_) => {}
+            hash_classification => return hash_classification,
+        }
+    }
+
+    let mut heuristic_classification = None;
+    if rule.has_heuristics() {
+        /* Path (metadata and hash) does match. Now check the content metrics */
+        match ClassifierTrait::classify(heuristic_classifier, entry, rule) {
+            Classification::Match(violation) => heuristic_classification = Some(violation),
+            classification => return classification,
+        }
+    }
 
-        match path_classification {
-            Classification::Error(_) => {
-                unreachable!("Classification::Error is not implemented for `PathClassifier`")
+    if rule.has_content() {
+        /* Path (metadata, hash and content metrics) does match. Now check the content */
+        match ClassifierTrait::classify(content_classifier, entry, rule) {
+            Classification::Match(violation) => {
+                match heuristic_classification
+                    .as_ref()
+                    .and_then(Violation::metrics)
+                {
+                    Some(metrics) => Classification::Match(violation.with_metrics(*metrics)),
+                    None => Classification::Match(violation),
+                }
             }
-            _ => path_classification,
+            classification => classification,
         }
+    } else if let Some(violation) = heuristic_classification {
+        Classification::Match(violation)
+    } else {
+        path_classification
     }
 }
 
@@ -89,9 +167,20 @@ mod test {
 
     fn test_classify_entry<D: DirEntryTrait>(entry: &D, rule: &Rule) -> Classification {
         let mut path_classifier = path_classifier::PathClassifier::new(entry);
+        let mut file_condition_classifier = FileConditionClassifier::new(entry);
+        let mut hash_classifier = hash_classifier::HashClassifier::new(entry);
+        let mut heuristic_classifier = HeuristicClassifier::new(entry);
         let mut content_classifier = content_classifier::ContentClassifier::new(entry);
 
-        classify_entry_with_rule(&mut path_classifier, &mut content_classifier, entry, rule)
+        classify_entry_with_rule(
+            &mut path_classifier,
+            &mut file_condition_classifier,
+            &mut hash_classifier,
+            &mut heuristic_classifier,
+            &mut content_classifier,
+            entry,
+            rule,
+        )
     }
 
     #[test]
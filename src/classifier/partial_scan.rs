@@ -0,0 +1,38 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Information about a file of which only parts of the content were scanned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PartialScan {
+    scanned_bytes: u64,
+    file_size: u64,
+}
+
+impl PartialScan {
+    pub(crate) fn new(scanned_bytes: u64, file_size: u64) -> Self {
+        PartialScan {
+            scanned_bytes,
+            file_size,
+        }
+    }
+
+    /// Return the number of bytes that were matched against the content patterns
+    pub fn scanned_bytes(&self) -> u64 {
+        self.scanned_bytes
+    }
+
+    /// Return the size of the file
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+}
+
+impl fmt::Display for PartialScan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "only {} of {} bytes were scanned",
+            self.scanned_bytes, self.file_size
+        )
+    }
+}
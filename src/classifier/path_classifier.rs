@@ -7,7 +7,7 @@ use crate::DirEntryTrait;
 
 pub struct PathClassifier {}
 
-impl<'a, D: DirEntryTrait> ClassifierTrait<D> for PathClassifier {
+impl<D: DirEntryTrait> ClassifierTrait<D> for PathClassifier {
     fn new(_: &D) -> Self {
         PathClassifier {}
     }
@@ -1,15 +1,24 @@
 use crate::classifier::content_classifier::*;
+use crate::classifier::{ContentMatch, ContentMetrics};
 use crate::errors::Error;
 use crate::Rule;
 use crate::{RuleTrait, Severity};
+use serde::{Serialize, Serializer};
 use std::error::Error as StdError;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Violation {
-    #[allow(unused)]
+    #[serde(
+        skip_serializing_if = "has_no_metadata",
+        serialize_with = "serialize_rule_metadata"
+    )]
     rule: Option<Rule>,
     name: String,
     severity: Severity,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    matches: Vec<ContentMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metrics: Option<ContentMetrics>,
 }
 
 impl Violation {
@@ -20,14 +29,31 @@ impl Violation {
             rule: Some(rule),
             name,
             severity,
+            matches: vec![],
+            metrics: None,
         }
     }
 
+    pub fn with_rule_and_matches(rule: Rule, matches: Vec<ContentMatch>) -> Self {
+        Violation {
+            matches,
+            ..Self::with_rule(rule)
+        }
+    }
+
+    /// Return the Violation with the content metrics that reached the Rule's heuristics
+    pub fn with_metrics(mut self, metrics: ContentMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     pub fn with_name_and_severity(name: String, severity: Severity) -> Self {
         Violation {
             rule: None,
             name,
             severity,
+            matches: vec![],
+            metrics: None,
         }
     }
 
@@ -45,9 +71,24 @@ impl Violation {
             rule: Some(rule),
             name,
             severity,
+            matches: vec![],
+            metrics: None,
         })
     }
 
+    /// Return the violated Rule (if the Violation was not caused by an error)
+    pub fn rule(&self) -> Option<&Rule> {
+        self.rule.as_ref()
+    }
+
+    /// Return the stable identifier of the violated Rule (or the name if none is defined)
+    pub fn id(&self) -> &str {
+        match self.rule {
+            Some(ref rule) => rule.id(),
+            None => &self.name,
+        }
+    }
+
     /// Return the name describing the Violation (e.g. the name of the violated Rule)
     pub fn name(&self) -> &String {
         &self.name
@@ -57,6 +98,29 @@ impl Violation {
     pub fn severity(&self) -> Severity {
         self.severity
     }
+
+    /// Return the locations where the file content matched the Rule's content pattern
+    pub fn matches(&self) -> &[ContentMatch] {
+        &self.matches
+    }
+
+    /// Return the content metrics that reached the Rule's heuristics
+    pub fn metrics(&self) -> Option<&ContentMetrics> {
+        self.metrics.as_ref()
+    }
+}
+
+fn has_no_metadata(rule: &Option<Rule>) -> bool {
+    rule.as_ref().is_none_or(|rule| rule.metadata().is_empty())
+}
+
+fn serialize_rule_metadata<S: Serializer>(
+    rule: &Option<Rule>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    rule.as_ref()
+        .map(|rule| rule.metadata())
+        .serialize(serializer)
 }
 
 impl From<&dyn StdError> for Violation {
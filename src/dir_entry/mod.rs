@@ -11,6 +11,12 @@ pub trait DirEntryTrait: Debug {
     /// See [`walkdir::DirEntry::path`] for more details
     fn path(&self) -> &Path;
 
+    /// The path relative to the root directory that was scanned.
+    ///
+    /// The root itself (e.g. if a single file is scanned) is relative to its parent directory.
+    /// Entries that were not found by scanning a root return the path as given
+    fn relative_path(&self) -> &Path;
+
     /// Return `true` if and only if this entry was created from a symbolic
     /// link. This is unaffected by the [`follow_links`] setting.
     ///
@@ -10,6 +10,7 @@ use std::path::PathBuf;
 #[derive(Debug, Clone)]
 pub struct DirEntry {
     raw: PathBuf,
+    relative_path: Option<PathBuf>,
     file_type: StandaloneFileType,
 }
 
@@ -31,9 +32,25 @@ impl DirEntry {
     ) -> Self {
         DirEntry {
             raw: raw.into(),
+            relative_path: None,
             file_type,
         }
     }
+
+    /// Return the entry with the path relative to the scanned `root` directory
+    ///
+    /// If the path is the `root` itself it is relative to its parent directory
+    pub fn with_root<P: AsRef<Path>>(mut self, root: P) -> Self {
+        let relative_path = match self.raw.strip_prefix(root) {
+            Ok(relative_path) if relative_path.as_os_str().is_empty() => {
+                self.raw.file_name().map(PathBuf::from)
+            }
+            Ok(relative_path) => Some(relative_path.to_owned()),
+            Err(_) => None,
+        };
+        self.relative_path = relative_path;
+        self
+    }
 }
 
 impl DirEntryTrait for DirEntry {
@@ -44,6 +61,13 @@ impl DirEntryTrait for DirEntry {
         &self.raw
     }
 
+    /// The path relative to the root directory that was scanned.
+    ///
+    /// The path as given if the entry was not created with a root
+    fn relative_path(&self) -> &Path {
+        self.relative_path.as_deref().unwrap_or(&self.raw)
+    }
+
     /// Return `true` if and only if this entry was created from a symbolic
     /// link. This is unaffected by the [`follow_links`] setting.
     ///
@@ -75,3 +99,28 @@ impl DirEntryTrait for DirEntry {
             .expect("An invalid DirEntry instance has been created. This must not have happened")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn relative_path_test() {
+        let entry = DirEntry::from_path_with_file_type(
+            "/var/www/uploads/shell.php",
+            StandaloneFileType::File,
+        );
+        assert_eq!(
+            Path::new("/var/www/uploads/shell.php"),
+            entry.relative_path()
+        );
+
+        let entry = entry.with_root("/var/www");
+        assert_eq!(Path::new("uploads/shell.php"), entry.relative_path());
+
+        let root =
+            DirEntry::from_path_with_file_type("/var/www/shell.php", StandaloneFileType::File)
+                .with_root("/var/www/shell.php");
+        assert_eq!(Path::new("shell.php"), root.relative_path());
+    }
+}
@@ -43,6 +43,11 @@ impl DirEntryTrait for DirEntry {
         &self.path_buf
     }
 
+    /// The path as given, because validated entries are not found by scanning a root
+    fn relative_path(&self) -> &Path {
+        &self.path_buf
+    }
+
     /// Return `true` if and only if this entry was created from a symbolic
     /// link. This is unaffected by the [`follow_links`] setting.
     ///
@@ -19,7 +19,6 @@ impl DirEntry {
     /// Return the depth at which this entry was created relative to the root.
     ///
     /// See [`walkdir::DirEntry::depth`] for more details
-    #[allow(unused)]
     fn depth(&self) -> usize {
         self.raw.depth()
     }
@@ -33,6 +32,17 @@ impl DirEntryTrait for DirEntry {
         self.raw.path()
     }
 
+    /// The path relative to the root directory that was scanned.
+    ///
+    /// The last [`depth`] components of the path (at least the file name)
+    fn relative_path(&self) -> &Path {
+        let path = self.raw.path();
+        match path.ancestors().nth(self.depth().max(1)) {
+            Some(root) => path.strip_prefix(root).unwrap_or(path),
+            None => path,
+        }
+    }
+
     /// Return `true` if and only if this entry was created from a symbolic
     /// link. This is unaffected by the [`follow_links`] setting.
     ///
@@ -52,7 +62,7 @@ impl DirEntryTrait for DirEntry {
 
         match walkdir_error.into_io_error() {
             Some(e) => Err(e),
-            None => Err(io::Error::new(io::ErrorKind::Other, "Loop error")),
+            None => Err(io::Error::other("Loop error")),
         }
     }
 
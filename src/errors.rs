@@ -19,6 +19,10 @@ error_chain! {
     // This section can be empty.
     foreign_links {
         Regex(::regex::Error);
+        Glob(::globset::Error);
+        Json(::serde_json::Error) #[cfg(feature = "json")];
+        Yaml(::serde_yaml::Error);
+        Toml(::toml::de::Error) #[cfg(feature = "toml")];
     }
 
     // Define additional `ErrorKind` variants.  Define custom responses with the
@@ -32,6 +36,10 @@ error_chain! {
             description("Invalid configuration file")
             display("Configuration file can not be loaded: '{}'", t)
         }
+        RuleError(t: String) {
+            description("Invalid rule")
+            display("Invalid rule: {}", t)
+        }
         SeverityError(t: String) {
             description("Invalid severity given")
             display("Given severity string '{}' can not be parsed", t)
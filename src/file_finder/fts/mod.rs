@@ -95,7 +95,7 @@ fn dir_entry_from_fts_entry(entry: &FTSENT) -> StandaloneDirEntry {
     )
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct FileFinder {}
 
 impl FileFinder {
@@ -113,7 +113,11 @@ impl FileFinderTrait for FileFinder {
         let entries = collect_dir_entries(&root.as_ref().to_string_lossy().into_owned());
 
         match entries {
-            Ok(entries) => entries.into_iter().filter(filter).collect(),
+            Ok(entries) => entries
+                .into_iter()
+                .map(|entry| entry.with_root(root.as_ref()))
+                .filter(filter)
+                .collect(),
             Err(error) => {
                 error!("{}", error);
                 vec![]
@@ -55,7 +55,7 @@ extern "C" {
 
 // Thread local vector to hold the found paths
 thread_local! {
-    static FOUND_PATHS: RefCell<Vec<StandaloneDirEntry>> = RefCell::new(vec![]);
+    static FOUND_PATHS: RefCell<Vec<StandaloneDirEntry>> = const { RefCell::new(vec![]) };
 }
 
 /// Callback for [`ftw`](https://linux.die.net/man/3/ftw)
@@ -98,7 +98,7 @@ extern "C" fn nftw_collector(
     0
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct FileFinder {}
 
 impl FileFinder {
@@ -114,9 +114,13 @@ impl FileFinderTrait for FileFinder {
     where
         F: FnMut(&Self::DirEntry) -> bool,
     {
-        let entries = collect_dir_entries_nftw(&root.as_ref().to_string_lossy().into_owned());
+        let entries = collect_dir_entries_nftw(&root.as_ref().to_string_lossy());
 
-        entries.into_iter().filter(filter).collect()
+        entries
+            .into_iter()
+            .map(|entry| entry.with_root(root.as_ref()))
+            .filter(filter)
+            .collect()
     }
 }
 
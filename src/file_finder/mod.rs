@@ -1,5 +1,6 @@
 use std::fmt::Debug;
 use std::path::Path;
+use std::time::SystemTime;
 
 #[cfg(feature = "fts")]
 pub mod fts;
@@ -16,30 +17,25 @@ pub trait FileFinderTrait {
 
     /// Return all [`DirEntry`s] that match at least one of the [`Rule`s] starting at `root`
     fn find<P: AsRef<Path> + Debug + Clone>(&self, root: P, rules: &[Rule]) -> Vec<Self::DirEntry> {
-        self.walk_dir(root, |entry: &Self::DirEntry| {
-            if entry.file_type().is_dir() {
-                return false;
-            }
-            if entry.file_type().is_symlink() {
-                return false;
-            }
-            let path_as_string = entry.path().to_string_lossy();
-
-            let mut store_entry = false;
-            for rule in rules {
-                // Check if the `Rule`'s path matches the current entry
-                if Matcher::match_path_str(rule, path_as_string.as_ref()) {
-                    // If the `Rule`'s path matches and the `Rule` is a whitelist-rule exit the loop
-                    // and ignore the entry
-                    if rule.severity() == Severity::WHITELIST {
-                        return false;
-                    }
-                    store_entry = true;
-                }
-            }
+        self.walk_dir(root, |entry: &Self::DirEntry| filter_entry(entry, rules))
+    }
 
-            store_entry
-        })
+    /// Return an iterator over all [`DirEntry`s] that match at least one of the [`Rule`s] starting
+    /// at `root`
+    ///
+    /// Entries are yielded while the directory tree is walked, if the implementation supports it
+    fn find_iter<'a, P: AsRef<Path> + Debug + Clone>(
+        &self,
+        root: P,
+        rules: &'a [Rule],
+    ) -> Box<dyn Iterator<Item = Self::DirEntry> + 'a>
+    where
+        Self::DirEntry: 'a,
+    {
+        Box::new(
+            self.walk_dir_iter(root)
+                .filter(move |entry| filter_entry(entry, rules)),
+        )
     }
 
     /// Walk through all files and directories under `root` and filter results with `filter`
@@ -50,8 +46,73 @@ pub trait FileFinderTrait {
     ) -> Vec<Self::DirEntry>
     where
         F: Fn(&Self::DirEntry) -> bool;
+
+    /// Return an iterator over all files and directories under `root`
+    ///
+    /// The default implementation collects all entries with [`walk_dir`] before they are yielded
+    fn walk_dir_iter<'a, P: AsRef<Path> + Debug + Clone>(
+        &self,
+        root: P,
+    ) -> Box<dyn Iterator<Item = Self::DirEntry> + 'a>
+    where
+        Self::DirEntry: 'a,
+    {
+        Box::new(self.walk_dir(root, |_| true).into_iter())
+    }
+}
+
+/// Return if the entry is a file that should be rated
+///
+/// The entry's path has to match at least one of the `rules` and none of the `WHITELIST` rules.
+/// For `WHITELIST` rules with a file condition the file has to fulfill the condition as well.
+/// `WHITELIST` rules with a hash list are ignored, they are applied by the classifier
+fn filter_entry<D: DirEntryTrait>(entry: &D, rules: &[Rule]) -> bool {
+    if entry.file_type().is_dir() {
+        return false;
+    }
+    if entry.file_type().is_symlink() {
+        return false;
+    }
+    let mut file_stat: Option<Option<FileStat>> = None;
+    let mut store_entry = false;
+    for rule in rules {
+        // Check if the `Rule`'s path matches the current entry
+        if Matcher::match_entry_path(rule, entry) {
+            // Known-good hashes are checked when the file is classified, so that each file is
+            // only hashed once
+            if rule.is_hash_allowlist() {
+                continue;
+            }
+            // If the `Rule`'s path matches and the `Rule` is a whitelist-rule exit the loop
+            // and ignore the entry (if the file fulfills the `Rule`'s file condition)
+            if rule.severity() == Severity::WHITELIST {
+                if let Some(file_condition) = rule.file_condition() {
+                    let stat = file_stat.get_or_insert_with(|| {
+                        entry.metadata().ok().map(|m| FileStat::from_metadata(&m))
+                    });
+                    if !stat.is_some_and(|stat| file_condition.matches(&stat, SystemTime::now())) {
+                        continue;
+                    }
+                }
+                return false;
+            }
+            store_entry = true;
+        }
+    }
+
+    store_entry
 }
 
 pub fn find_files<P: AsRef<Path> + Debug + Clone>(root: P, rules: &[Rule]) -> Vec<WalkdirDirEntry> {
     self::walkdir::FileFinder::find(&self::walkdir::FileFinder::new(), root, rules)
 }
+
+/// Return an iterator over the files under `root` that match at least one of the `rules`
+///
+/// The files are yielded while the directory tree is walked
+pub fn find_files_iter<P: AsRef<Path> + Debug + Clone>(
+    root: P,
+    rules: &[Rule],
+) -> impl Iterator<Item = WalkdirDirEntry> + '_ {
+    self::walkdir::FileFinder::new().find_iter(root, rules)
+}
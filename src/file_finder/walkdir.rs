@@ -4,7 +4,7 @@ use std::fmt::Debug;
 use std::path::Path;
 use walkdir::WalkDir;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct FileFinder {}
 
 impl FileFinder {
@@ -38,4 +38,26 @@ impl FileFinderTrait for FileFinder {
 
         result
     }
+
+    fn walk_dir_iter<'a, P: AsRef<Path> + Debug + Clone>(
+        &self,
+        root: P,
+    ) -> Box<dyn Iterator<Item = Self::DirEntry> + 'a>
+    where
+        Self::DirEntry: 'a,
+    {
+        info!("Search files in directory {:?}", root);
+
+        Box::new(
+            WalkDir::new(root)
+                .into_iter()
+                .filter_map(|entry| match entry {
+                    Ok(entry) => Some(WalkdirDirEntry::from_dir_entry(entry)),
+                    Err(e) => {
+                        error!("{}", e);
+                        None
+                    }
+                }),
+        )
+    }
 }
@@ -0,0 +1,191 @@
+use crate::dir_entry::DirEntryTrait;
+use crate::errors::*;
+use crate::file_finder::walkdir::FileFinder;
+use crate::file_finder::FileFinderTrait;
+use crate::rule::{HashAlgorithm, HashList};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Size of the buffer used to stream the file content into the hasher
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Lazily computed hashes of a file's content
+///
+/// Each hash is only computed once it is requested, and then cached
+pub(crate) struct FileHashes {
+    path: PathBuf,
+    /// Content that is used instead of reading the file
+    content: Option<Vec<u8>>,
+    hashes: HashMap<HashAlgorithm, String>,
+}
+
+impl FileHashes {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileHashes {
+            path: path.into(),
+            content: None,
+            hashes: HashMap::new(),
+        }
+    }
+
+    /// Create hashes of the given `content` instead of the file's content
+    pub fn with_content<P: Into<PathBuf>, C: Into<Vec<u8>>>(path: P, content: C) -> Self {
+        FileHashes {
+            content: Some(content.into()),
+            ..Self::new(path)
+        }
+    }
+
+    /// Return the lowercase hex encoded hash of the content computed with `algorithm`
+    pub fn get(&mut self, algorithm: HashAlgorithm) -> io::Result<&str> {
+        if !self.hashes.contains_key(&algorithm) {
+            let hash = match self.content {
+                Some(ref content) => compute_hash(algorithm, content.as_slice())?,
+                None => {
+                    trace!("Will compute {} of file {}", algorithm, self.path.display());
+                    compute_hash(algorithm, File::open(&self.path)?)?
+                }
+            };
+            self.hashes.insert(algorithm, hash);
+        }
+
+        Ok(&self.hashes[&algorithm])
+    }
+
+    /// Return if any hash of the content is contained in `hash_list`
+    pub fn is_contained_in(&mut self, hash_list: &HashList) -> io::Result<bool> {
+        for algorithm in hash_list.algorithms() {
+            if hash_list.contains(algorithm, self.get(algorithm)?) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+fn compute_hash<R: Read>(algorithm: HashAlgorithm, reader: R) -> io::Result<String> {
+    match algorithm {
+        HashAlgorithm::Md5 => digest_reader::<Md5, R>(reader),
+        HashAlgorithm::Sha1 => digest_reader::<Sha1, R>(reader),
+        HashAlgorithm::Sha256 => digest_reader::<Sha256, R>(reader),
+    }
+}
+
+fn digest_reader<D: Digest, R: Read>(mut reader: R) -> io::Result<String> {
+    let mut hasher = D::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+    loop {
+        let read_bytes = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read_bytes) => read_bytes,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buffer[..read_bytes]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Build a manifest with the SHA-256 hash of every file under `root`
+///
+/// Each line contains the hash and the path relative to `root` in the format of `sha256sum`, so
+/// the manifest of a clean vendor release can be used as list of known-good hashes
+pub fn build_hash_manifest<P: AsRef<Path>>(root: P) -> Result<String> {
+    let root = root.as_ref();
+    let mut files: Vec<PathBuf> = FileFinder::new()
+        .walk_dir(root, |entry| entry.file_type().is_file())
+        .iter()
+        .map(|entry| entry.path().to_owned())
+        .collect();
+    files.sort();
+
+    let mut manifest = String::new();
+    for file in files {
+        let hash = match File::open(&file).and_then(|f| compute_hash(HashAlgorithm::Sha256, f)) {
+            Ok(hash) => hash,
+            Err(e) => bail!("Could not hash file '{}': {}", file.display(), e),
+        };
+        let relative_path = file.strip_prefix(root).unwrap_or(&file);
+        manifest.push_str(&format!("{}  {}\n", hash, relative_path.display()));
+    }
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn get_test() {
+        let mut hashes = FileHashes::with_content("empty.php", "");
+
+        assert_eq!(
+            "d41d8cd98f00b204e9800998ecf8427e",
+            hashes.get(HashAlgorithm::Md5).unwrap()
+        );
+        assert_eq!(
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            hashes.get(HashAlgorithm::Sha1).unwrap()
+        );
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            hashes.get(HashAlgorithm::Sha256).unwrap()
+        );
+    }
+
+    #[test]
+    fn get_file_test() {
+        let mut hashes = FileHashes::new(format!(
+            "{}/tests/resources/files/dezmond.php",
+            env!("CARGO_MANIFEST_DIR")
+        ));
+
+        assert_eq!(
+            "cdd8f6ccca624908eb49cb34124855597326cecc099532342f6b8968d0d1a962",
+            hashes.get(HashAlgorithm::Sha256).unwrap()
+        );
+
+        let mut not_existing = FileHashes::new("not-existing-file.php");
+        assert!(not_existing.get(HashAlgorithm::Md5).is_err());
+    }
+
+    #[test]
+    fn build_hash_manifest_test() {
+        let root = format!("{}/tests/resources/files", env!("CARGO_MANIFEST_DIR"));
+        let manifest = build_hash_manifest(&root).unwrap();
+
+        assert!(manifest.contains(
+            "cdd8f6ccca624908eb49cb34124855597326cecc099532342f6b8968d0d1a962  dezmond.php\n"
+        ));
+        let mut list = HashList::default();
+        for line in manifest.lines() {
+            list.insert(line.split_whitespace().next().unwrap())
+                .unwrap();
+        }
+        assert!(!list.is_empty());
+    }
+
+    #[test]
+    fn is_contained_in_test() {
+        let mut hashes = FileHashes::with_content("empty.php", "");
+        let list = HashList::from_hashes(&["da39a3ee5e6b4b0d3255bfef95601890afd80709"]).unwrap();
+        let other_list =
+            HashList::from_hashes(&["0000000000000000000000000000000000000000"]).unwrap();
+
+        assert!(hashes.is_contained_in(&list).unwrap());
+        assert!(!hashes.is_contained_in(&other_list).unwrap());
+    }
+}
@@ -14,16 +14,21 @@ pub mod classifier;
 mod dir_entry;
 pub mod errors;
 pub mod file_finder;
+mod file_hash;
 mod fs;
 mod join;
 mod matcher;
 pub mod rating;
+pub mod report;
 mod rule;
 mod severity;
+#[cfg(test)]
+mod test_helpers;
 
 pub use crate::dir_entry::*;
 pub use crate::errors::*;
 pub use crate::file_finder::find_files;
+pub use crate::file_hash::build_hash_manifest;
 pub use crate::join::join_violations;
 pub use crate::rating::*;
 pub use crate::rule::*;
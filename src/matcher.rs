@@ -1,16 +1,62 @@
+use crate::classifier::{ContentMatch, TextPosition};
 use crate::dir_entry::*;
-use crate::rule::Rule;
+use crate::rule::ContentPattern;
+use crate::rule::MatchTarget;
 use crate::rule::RulePath;
 use crate::rule::RuleTrait;
+use regex::bytes::Regex;
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Maximum number of content matches to collect per rule
+const MAX_CONTENT_MATCHES: usize = 10;
 
 pub struct Matcher {}
 
 impl Matcher {
     /// Check if the entry's path matches the given rule
+    ///
+    /// The rule's path pattern is matched against the part of the path selected by the rule's
+    /// `match_on` target
     pub fn match_entry_path<C, P: RuleTrait<C>, D: DirEntryTrait>(rule: &P, entry: &D) -> bool {
-        let path_as_string = entry.path().to_string_lossy();
+        if !Matcher::match_extension(rule, &entry.file_name().to_string_lossy()) {
+            return false;
+        }
+
+        let target = match rule.match_on() {
+            MatchTarget::FileName => entry.file_name().to_string_lossy(),
+            MatchTarget::Dir => entry
+                .path()
+                .parent()
+                .map_or(Cow::Borrowed(""), Path::to_string_lossy),
+            MatchTarget::Path => entry.path().to_string_lossy(),
+            MatchTarget::RelativePath => entry.relative_path().to_string_lossy(),
+        };
+
+        Matcher::match_path_str(rule, target.as_ref())
+    }
+
+    /// Check if the file name has one of the extensions of the given rule
+    ///
+    /// Leading dots are not extension separators (`.htaccess` has no extension). Rules without
+    /// extensions match every file name
+    pub fn match_extension<C, P: RuleTrait<C>>(rule: &P, file_name: &str) -> bool {
+        let extensions = rule.extensions();
+        if extensions.is_empty() {
+            return true;
+        }
 
-        Matcher::match_path_str(rule, path_as_string.as_ref())
+        let is_listed = |extension: &str| {
+            extensions
+                .iter()
+                .any(|listed| listed.eq_ignore_ascii_case(extension))
+        };
+        let mut file_extensions = file_name.trim_start_matches('.').split('.').skip(1);
+        if rule.any_extension() {
+            file_extensions.any(is_listed)
+        } else {
+            file_extensions.last().is_some_and(is_listed)
+        }
     }
 
     /// Check if the given path matches the given rule
@@ -38,21 +84,94 @@ impl Matcher {
                 );
                 r.is_match(path_as_string)
             }
+            RulePath::Glob(g) => {
+                trace!(
+                    "Match rule '{}' with glob '{}' against path '{}'",
+                    rule.name(),
+                    g.glob(),
+                    path_as_string
+                );
+                g.is_match(path_as_string)
+            }
         }
     }
+}
 
-    /// Check if the entry's content matches the given rule
-    pub fn match_entry_content(rule: &Rule, content: &str) -> bool {
-        match rule.content() {
-            Some(content_pattern) => {
-                trace!(
-                    "Match rule '{}' with pattern '{}'",
-                    rule.name(),
-                    content_pattern
+/// Matches of a content pattern collected over the parts of a file's content
+///
+/// The patterns of a content group may match in different parts, while the patterns of a `none`
+/// list must not match in any part
+pub struct ContentScan {
+    pattern: ContentPattern,
+    /// Regular expressions of the pattern and if they must match
+    regexes: Vec<(Regex, bool)>,
+    /// Which of the `regexes` matched in any part
+    hits: Vec<bool>,
+    matches: Vec<ContentMatch>,
+}
+
+impl ContentScan {
+    pub fn new(pattern: ContentPattern) -> Self {
+        let regexes: Vec<(Regex, bool)> = pattern
+            .regexes()
+            .into_iter()
+            .map(|(regex, positive)| (regex.clone(), positive))
+            .collect();
+        ContentScan {
+            hits: vec![false; regexes.len()],
+            pattern,
+            regexes,
+            matches: vec![],
+        }
+    }
+
+    /// Return the content pattern
+    pub fn pattern(&self) -> &ContentPattern {
+        &self.pattern
+    }
+
+    /// Match the part of the content that starts at byte `offset` and text `position`
+    /// (`None` if the lines before the part are unknown)
+    pub fn scan(&mut self, content: &[u8], offset: usize, position: Option<TextPosition>) {
+        for ((regex, positive), hit) in self.regexes.iter().zip(self.hits.iter_mut()) {
+            if !regex.is_match(content) {
+                continue;
+            }
+            *hit = true;
+            if *positive && self.matches.len() < MAX_CONTENT_MATCHES {
+                self.matches.extend(
+                    regex
+                        .captures_iter(content)
+                        .take(MAX_CONTENT_MATCHES)
+                        .map(|captures| ContentMatch::from_captures(content, regex, &captures))
+                        .map(|m| m.shifted(offset, position)),
                 );
-                content_pattern.is_match(content)
             }
-            None => false,
         }
     }
+
+    /// Return if scanning more parts can not change the result
+    ///
+    /// This is the case once the pattern matches and it has no patterns that must not match
+    pub fn is_settled(&self) -> bool {
+        self.regexes.iter().all(|(_, positive)| *positive)
+            && self.pattern.is_match_with_hits(&self.hits)
+    }
+
+    /// Return the locations of the matches, or `None` if the pattern does not match
+    ///
+    /// The locations may be empty if the content group only consists of patterns that must not
+    /// match
+    pub fn finish(self) -> Option<Vec<ContentMatch>> {
+        if !self.pattern.is_match_with_hits(&self.hits) {
+            return None;
+        }
+
+        let mut matches = self.matches;
+        matches.sort_by_key(ContentMatch::offset);
+        matches.dedup();
+        matches.truncate(MAX_CONTENT_MATCHES);
+
+        Some(matches)
+    }
 }
@@ -1,13 +1,18 @@
+mod stream;
 mod summary;
 
+pub use self::stream::rate_entries_streaming;
 pub use self::summary::Summary;
-use crate::classifier::{classify_entry, Violation};
+use crate::classifier::{scan_entry, PartialScan, Violation};
 use crate::dir_entry::DirEntryTrait;
 use crate::join::join_violations;
 use crate::severity::Severity;
 use crate::Rule;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use std::cmp::Reverse;
 use std::fmt;
+use std::sync::Arc;
 
 pub fn rate_entries<'a, D: DirEntryTrait>(entries: &'a [D], rules: &'a [Rule]) -> Vec<Rating<'a>> {
     debug!("Will rate entries");
@@ -20,11 +25,51 @@ pub fn rate_entries<'a, D: DirEntryTrait>(entries: &'a [D], rules: &'a [Rule]) -
     result
 }
 
+/// Rate the entries using `jobs` threads
+///
+/// The result is in the same order as `entries`, independent of the number of threads
+pub fn rate_entries_parallel<D: DirEntryTrait + Clone + Send + 'static>(
+    entries: &[D],
+    rules: &[Rule],
+    jobs: usize,
+) -> Vec<Rating<'static>> {
+    let mut ratings = Vec::with_capacity(entries.len());
+    rate_entries_streaming(entries.iter().cloned(), rules, jobs, |rating| {
+        ratings.push(rating)
+    });
+
+    ratings
+}
+
 pub fn rate_entry<'a, D: DirEntryTrait>(entry: &'a D, rules: &[Rule]) -> Rating<'a> {
     info!("Will rate entry {:?}", entry);
-    let violations: Vec<Violation> = classify_entry(entry, rules);
+    let (violations, partial_scan) = scan_entry(entry, rules);
 
-    let rating = violations.iter().fold(0, |acc, violation| {
+    build_rating(entry, violations, partial_scan)
+}
+
+fn build_rating<D: DirEntryTrait>(
+    entry: &D,
+    violations: Vec<Violation>,
+    partial_scan: Option<PartialScan>,
+) -> Rating<'_> {
+    let rating = sum_violations(&violations);
+    debug!("Did rate entry {:?} (rating: {})", entry, rating);
+    Rating::new(entry, rating, violations).with_partial_scan(partial_scan)
+}
+
+fn build_owned_rating<D: DirEntryTrait + 'static>(
+    entry: D,
+    violations: Vec<Violation>,
+    partial_scan: Option<PartialScan>,
+) -> Rating<'static> {
+    let rating = sum_violations(&violations);
+    debug!("Did rate entry {:?} (rating: {})", entry, rating);
+    Rating::with_owned_entry(entry, rating, violations).with_partial_scan(partial_scan)
+}
+
+fn sum_violations(violations: &[Violation]) -> isize {
+    violations.iter().fold(0, |acc, violation| {
         trace!(
             "  Update rating {} {} {}",
             acc,
@@ -33,37 +78,78 @@ pub fn rate_entry<'a, D: DirEntryTrait>(entry: &'a D, rules: &[Rule]) -> Rating<
         );
 
         acc + violation.severity() as isize
-    });
-    debug!("Did rate entry {:?} (rating: {})", entry, rating);
-    Rating::new(entry, rating, violations)
+    })
 }
 
 pub fn sort_ratings<'a>(ratings: &[Rating<'a>]) -> Vec<Rating<'a>> {
     let mut copy = ratings.to_vec();
 
-    copy.sort_unstable_by_key(|b| Reverse(b.rating()));
+    sort_ratings_in_place(&mut copy);
 
     copy
 }
 
+/// Sort the ratings by their rating in descending order without copying them
+pub fn sort_ratings_in_place(ratings: &mut [Rating<'_>]) {
+    ratings.sort_unstable_by_key(|b| Reverse(b.rating()));
+}
+
+#[derive(Debug, Clone)]
+enum RatedEntry<'a> {
+    Borrowed(&'a dyn DirEntryTrait),
+    Owned(Arc<dyn DirEntryTrait>),
+}
+
 #[derive(Debug, Clone)]
 pub struct Rating<'a> {
-    entry: &'a dyn DirEntryTrait,
+    entry: RatedEntry<'a>,
     rating: isize,
     violations: Vec<Violation>,
+    partial_scan: Option<PartialScan>,
 }
 
 impl<'a> Rating<'a> {
     pub fn new(entry: &'a dyn DirEntryTrait, rating: isize, violations: Vec<Violation>) -> Self {
         Rating {
-            entry,
+            entry: RatedEntry::Borrowed(entry),
+            rating,
+            violations,
+            partial_scan: None,
+        }
+    }
+
+    /// Create a Rating that takes ownership of the `entry`
+    pub fn with_owned_entry<D: DirEntryTrait + 'static>(
+        entry: D,
+        rating: isize,
+        violations: Vec<Violation>,
+    ) -> Rating<'static> {
+        Rating {
+            entry: RatedEntry::Owned(Arc::new(entry)),
             rating,
             violations,
+            partial_scan: None,
         }
     }
 
     pub fn entry(&self) -> &dyn DirEntryTrait {
-        self.entry
+        match &self.entry {
+            RatedEntry::Borrowed(entry) => *entry,
+            RatedEntry::Owned(entry) => entry.as_ref(),
+        }
+    }
+
+    /// Return the Rating with the information if the content was only partially scanned
+    pub fn with_partial_scan(mut self, partial_scan: Option<PartialScan>) -> Self {
+        self.partial_scan = partial_scan;
+        self
+    }
+
+    /// Return how much of the content was scanned, if the file was only partially scanned
+    ///
+    /// Content patterns may have missed a match in the parts that were not scanned
+    pub fn partial_scan(&self) -> Option<&PartialScan> {
+        self.partial_scan.as_ref()
     }
 
     pub fn violations(&self) -> &Vec<Violation> {
@@ -89,7 +175,7 @@ impl<'a> Rating<'a> {
     }
 
     pub fn description(&self) -> String {
-        let path_as_string: String = self.entry.path().to_string_lossy().into_owned();
+        let path_as_string: String = self.entry().path().to_string_lossy().into_owned();
 
         format!(
             "[{}] {} (Rules: {})",
@@ -106,24 +192,30 @@ impl<'a> fmt::Display for Rating<'a> {
     }
 }
 
+impl<'a> Serialize for Rating<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Rating", 5)?;
+        state.serialize_field("path", &self.entry().path().to_string_lossy())?;
+        state.serialize_field("rating", &self.rating)?;
+        state.serialize_field("description", self.rating_description())?;
+        state.serialize_field("violations", &self.violations)?;
+        match self.partial_scan {
+            Some(ref partial_scan) => state.serialize_field("partial_scan", partial_scan)?,
+            None => state.skip_field("partial_scan")?,
+        }
+        state.end()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::fs::StandaloneFileType;
     use crate::severity::Severity;
+    use crate::test_helpers::{get_test_dir_entry, TempDir};
     use crate::Rule;
     use crate::StandaloneDirEntry;
 
-    fn get_test_dir_entry(file: &str) -> StandaloneDirEntry {
-        StandaloneDirEntry::from_path(format!(
-            "{}{}{}",
-            env!("CARGO_MANIFEST_DIR"),
-            "/tests/resources/files/",
-            file
-        ))
-        .unwrap()
-    }
-
     mod rate_entry {
         use super::*;
         use crate::RawPath;
@@ -211,6 +303,216 @@ mod test {
             assert_eq!(Severity::NOTICE as isize, rating.rating());
         }
 
+        #[test]
+        fn rate_entry_with_file_condition_test() {
+            use crate::rule::RawFileCondition;
+            use std::os::unix::fs::PermissionsExt;
+
+            let dir = TempDir::new("file-condition-test");
+            let writable = dir.file("writable.php", "<?php echo 'upload';");
+            let protected = dir.file("protected.php", "<?php echo 'upload';");
+            for (path, mode) in &[(&writable, 0o666), (&protected, 0o644)] {
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(*mode)).unwrap();
+            }
+            let rules = vec![Rule::with_path(
+                "writable upload",
+                Severity::MAJOR,
+                RawPath::with_path("hackscanner-file-condition-test-"),
+            )
+            .unwrap()
+            .with_file_condition(RawFileCondition {
+                world_writable: Some(true),
+                modified_within: Some(3 * 24 * 60 * 60),
+                ..RawFileCondition::default()
+            })
+            .unwrap()];
+
+            let writable_entry = StandaloneDirEntry::from_path(&writable).unwrap();
+            let protected_entry = StandaloneDirEntry::from_path(&protected).unwrap();
+            let writable_rating = rate_entry(&writable_entry, &rules);
+            let protected_rating = rate_entry(&protected_entry, &rules);
+
+            assert_eq!(Severity::MAJOR as isize, writable_rating.rating());
+            assert_eq!(
+                "writable upload",
+                join_violations(writable_rating.violations())
+            );
+            assert!(protected_rating.violations().is_empty());
+        }
+
+        #[test]
+        fn rate_entry_with_heuristics_test() {
+            use crate::rule::{get_builtin_rules, Heuristics};
+
+            let dir = TempDir::new("heuristics-test");
+            let blob = "ZXZhbCgkX1BPU1RbJ2NtZCddKTs".repeat(200);
+            let path = dir.file(
+                "blob.php",
+                format!("<?php\neval(base64_decode('{}'));\n", blob),
+            );
+            let entry = StandaloneDirEntry::from_path(&path).unwrap();
+            let mut rules = get_builtin_rules();
+            rules.push(
+                Rule::with_path_and_content(
+                    "eval with long line",
+                    Severity::CRITICAL,
+                    RawPath::with_path(".php"),
+                    r"\beval\(",
+                )
+                .unwrap()
+                .with_heuristics(Heuristics {
+                    min_longest_line: Some(1024),
+                    ..Heuristics::default()
+                })
+                .unwrap(),
+            );
+
+            let rating = rate_entry(&entry, &rules);
+
+            let blob_violation = rating
+                .violations()
+                .iter()
+                .find(|v| v.name() == "php::heuristic::blob")
+                .expect("Blob heuristic must match");
+            assert_eq!(blob.len(), blob_violation.metrics().unwrap().blob_size());
+            // Content matches and metrics of the same Rule are combined
+            let combined_violation = rating
+                .violations()
+                .iter()
+                .find(|v| v.name() == "eval with long line")
+                .expect("Rule with heuristics and content must match");
+            assert_eq!(1, combined_violation.matches().len());
+            assert!(combined_violation.metrics().unwrap().longest_line() > 1024);
+            assert!(!rating
+                .violations()
+                .iter()
+                .any(|v| v.name() == "php::heuristic::long_line"));
+        }
+
+        #[test]
+        fn rate_entry_builtin_typo3_mixed_case_test() {
+            use crate::rule::{get_builtin_rules, RuleTrait};
+
+            let rules: Vec<Rule> = get_builtin_rules()
+                .into_iter()
+                .filter(|rule| rule.name().starts_with("typo3::") && !rule.has_content())
+                .collect();
+            let rate = |path: &str| {
+                let entry =
+                    StandaloneDirEntry::from_path_with_file_type(path, StandaloneFileType::File);
+                join_violations(rate_entry(&entry, &rules).violations())
+            };
+
+            assert_eq!(
+                "typo3::file::php-in-uploads",
+                rate("/var/www/Uploads/b.php")
+            );
+            assert_eq!(
+                "typo3::file::php-in-fileadmin",
+                rate("/var/www/FileAdmin/a.PHTML")
+            );
+            assert_eq!(
+                "typo3::file::php-in-typo3temp",
+                rate("/var/www/Typo3Temp/x.php")
+            );
+            assert_eq!(
+                "typo3::file::php-in-l10n",
+                rate("/var/www/TYPO3CONF/L10N/de/x.php")
+            );
+            assert_eq!("", rate("/var/www/Uploads/b.txt"));
+        }
+
+        #[test]
+        fn rate_entry_with_extensions_test() {
+            use crate::rule::get_builtin_rules;
+
+            let dir = TempDir::new("extensions-test");
+            let rate = |file_name: &str| {
+                let path = dir.file(file_name, "<?php eval($_POST['cmd']);");
+                let entry = StandaloneDirEntry::from_path(&path).unwrap();
+                let rating = rate_entry(&entry, &get_builtin_rules());
+
+                join_violations(rating.violations())
+            };
+
+            assert_eq!("php::content::eval", rate("shell.phtml"));
+            assert_eq!("php::content::eval", rate("shell.PHP5"));
+            assert_eq!("php::content::eval", rate("shell.php8"));
+            assert_eq!("php::content::eval", rate("shell.inc"));
+            assert_eq!("", rate("shell.php.txt"));
+            assert_eq!("", rate("shell.txt"));
+
+            let rules = vec![
+                Rule::with_path("double", Severity::MAJOR, RawPath::with_path(""))
+                    .unwrap()
+                    .with_extensions(&[".PHP"])
+                    .with_any_extension(true),
+            ];
+            let entry = StandaloneDirEntry::from_path_with_file_type(
+                "uploads/image.php.jpg",
+                StandaloneFileType::File,
+            );
+            assert_eq!(
+                "double",
+                join_violations(rate_entry(&entry, &rules).violations())
+            );
+            let entry = StandaloneDirEntry::from_path_with_file_type(
+                "uploads/.php",
+                StandaloneFileType::File,
+            );
+            assert_eq!("", join_violations(rate_entry(&entry, &rules).violations()));
+        }
+
+        #[test]
+        fn rate_entry_with_hash_test() {
+            let entry = get_test_dir_entry("dezmond.php");
+            let rules = vec![
+                Rule::with_path("IOC", Severity::CRITICAL, RawPath::with_path(".php"))
+                    .unwrap()
+                    .with_hashes(&["04efcc0fa123c454c510df5082ba9da0"])
+                    .unwrap(),
+                Rule::with_path("Other IOC", Severity::CRITICAL, RawPath::with_path(".php"))
+                    .unwrap()
+                    .with_hashes(&["1d12fc3e903d63d342f312df4e0cead6"])
+                    .unwrap(),
+            ];
+
+            let rating = rate_entry(&entry, &rules);
+
+            assert_eq!(Severity::CRITICAL as isize, rating.rating());
+            assert_eq!("IOC", join_violations(rating.violations()));
+        }
+
+        #[test]
+        fn rate_entry_with_known_good_hash_test() {
+            let entry = get_test_dir_entry("dezmond.php");
+            let rules = vec![
+                Rule::new(
+                    "dezmond",
+                    Severity::CRITICAL,
+                    RawPath::with_path(".php"),
+                    Some("dezmond".to_string()),
+                )
+                .unwrap(),
+                Rule::with_path("known-good", Severity::WHITELIST, RawPath::with_path(""))
+                    .unwrap()
+                    .with_hashes(&[
+                        "cdd8f6ccca624908eb49cb34124855597326cecc099532342f6b8968d0d1a962",
+                    ])
+                    .unwrap(),
+            ];
+
+            let rating = rate_entry(&entry, &rules);
+
+            // The content rules are not applied to known-good files
+            assert_eq!(Severity::WHITELIST as isize, rating.rating());
+            assert_eq!("known-good", join_violations(rating.violations()));
+
+            let other_entry = get_test_dir_entry("tx_mocfilemanager.php");
+            let rating = rate_entry(&other_entry, &rules);
+            assert!(rating.violations().is_empty());
+        }
+
         #[test]
         fn rate_entry_non_existing_file() {
             let entry = StandaloneDirEntry::from_path_with_file_type(
@@ -419,4 +721,31 @@ mod test {
             );
         }
     }
+
+    mod rate_entries_parallel {
+        use super::*;
+        use crate::rule::get_builtin_rules;
+        use crate::test_helpers::get_test_dir_entries;
+
+        #[test]
+        fn rate_entries_parallel_test() {
+            let entries = get_test_dir_entries();
+            let rules = get_builtin_rules();
+
+            let sequential = rate_entries(&entries, &rules);
+            for jobs in &[0, 1, 3, 16] {
+                let parallel = rate_entries_parallel(&entries, &rules, *jobs);
+
+                assert_eq!(sequential.len(), parallel.len());
+                for (expected, actual) in sequential.iter().zip(parallel.iter()) {
+                    assert_eq!(expected.entry().path(), actual.entry().path());
+                    assert_eq!(expected.rating(), actual.rating());
+                    assert_eq!(
+                        join_violations(expected.violations()),
+                        join_violations(actual.violations())
+                    );
+                }
+            }
+        }
+    }
 }
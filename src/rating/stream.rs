@@ -0,0 +1,253 @@
+use super::{build_owned_rating, Rating};
+use crate::classifier::{scan_entry, PartialScan, Violation};
+use crate::dir_entry::DirEntryTrait;
+use crate::Rule;
+use std::collections::BTreeMap;
+use std::panic;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+
+/// Rate the entries while they are yielded by `entries` and pass each Rating to `callback`
+///
+/// The entries are classified using `jobs` threads. `callback` is invoked on the calling thread
+/// in the same order as `entries` yields the entries, independent of the number of threads.
+pub fn rate_entries_streaming<D, I, F>(entries: I, rules: &[Rule], jobs: usize, mut callback: F)
+where
+    D: DirEntryTrait + Send + 'static,
+    I: IntoIterator<Item = D>,
+    F: FnMut(Rating<'static>),
+{
+    debug!("Will rate entries");
+    if jobs <= 1 {
+        for entry in entries {
+            let (violations, partial_scan) = scan_entry(&entry, rules);
+            callback(build_owned_rating(entry, violations, partial_scan));
+        }
+    } else {
+        rate_entries_streaming_parallel(entries, rules, jobs, &mut callback);
+    }
+    debug!("Did rate entries");
+}
+
+fn rate_entries_streaming_parallel<D, I, F>(entries: I, rules: &[Rule], jobs: usize, callback: F)
+where
+    D: DirEntryTrait + Send + 'static,
+    I: IntoIterator<Item = D>,
+    F: FnMut(Rating<'static>),
+{
+    let mut reorder_buffer = ReorderBuffer::new(callback);
+    // The work queue is bounded, so that the walk does not run ahead of the classification
+    let queue_size = jobs * 2;
+    let (work_sender, work_receiver) = mpsc::sync_channel::<(usize, D)>(queue_size);
+    let work_receiver = Mutex::new(work_receiver);
+    let (result_sender, result_receiver) = mpsc::channel::<WorkerResult<D>>();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let work_receiver = &work_receiver;
+            let result_sender = result_sender.clone();
+            scope.spawn(move || loop {
+                let work = work_receiver.lock().unwrap().recv();
+                let (index, entry) = match work {
+                    Ok(work) => work,
+                    Err(_) => break,
+                };
+                // A panic is passed on to the calling thread, which would otherwise wait for the
+                // entry's result forever
+                let scan_result =
+                    panic::catch_unwind(panic::AssertUnwindSafe(|| scan_entry(&entry, rules)));
+                if result_sender.send((index, entry, scan_result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(result_sender);
+
+        for work in entries.into_iter().enumerate() {
+            // While a slow entry holds back the results of the following ones, wait for it instead
+            // of buffering more results
+            while reorder_buffer.len() >= queue_size {
+                match result_receiver.recv() {
+                    Ok(result) => reorder_buffer.push(resume_worker_panic(result)),
+                    Err(_) => break,
+                }
+            }
+            if work_sender.send(work).is_err() {
+                break;
+            }
+            while let Ok(result) = result_receiver.try_recv() {
+                reorder_buffer.push(resume_worker_panic(result));
+            }
+        }
+        drop(work_sender);
+
+        for result in result_receiver {
+            reorder_buffer.push(resume_worker_panic(result));
+        }
+    });
+}
+
+/// Violations of an entry and if it was only partially scanned
+type ScanResult = (Vec<Violation>, Option<PartialScan>);
+
+/// Index, entry and scan result (or panic) sent back by a worker thread
+type WorkerResult<D> = (usize, D, thread::Result<ScanResult>);
+
+/// Return the worker's result, or continue the worker's panic on the calling thread
+///
+/// Unwinding drops the work queue's sender, so that the other workers stop
+fn resume_worker_panic<D>((index, entry, scan_result): WorkerResult<D>) -> (usize, D, ScanResult) {
+    match scan_result {
+        Ok(scan_result) => (index, entry, scan_result),
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Buffer to pass the results to the callback in the order of the entries
+struct ReorderBuffer<D, F> {
+    next_index: usize,
+    pending: BTreeMap<usize, (D, ScanResult)>,
+    callback: F,
+}
+
+impl<D, F> ReorderBuffer<D, F>
+where
+    D: DirEntryTrait + 'static,
+    F: FnMut(Rating<'static>),
+{
+    fn new(callback: F) -> Self {
+        ReorderBuffer {
+            next_index: 0,
+            pending: BTreeMap::new(),
+            callback,
+        }
+    }
+
+    /// Return the number of results waiting for the result of an earlier entry
+    fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn push(&mut self, (index, entry, scan_result): (usize, D, ScanResult)) {
+        self.pending.insert(index, (entry, scan_result));
+        while let Some((entry, (violations, partial_scan))) = self.pending.remove(&self.next_index)
+        {
+            (self.callback)(build_owned_rating(entry, violations, partial_scan));
+            self.next_index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fs::FileTypeTrait;
+    use crate::join::join_violations;
+    use crate::rule::{get_builtin_rules, MatchTarget, RawPath};
+    use crate::test_helpers::get_test_dir_entries;
+    use crate::{rate_entries, Severity, StandaloneDirEntry};
+    use std::ffi::OsStr;
+    use std::path::Path;
+    use std::time::Duration;
+    use std::{fs, io};
+
+    #[test]
+    fn reorder_buffer_test() {
+        let entries = get_test_dir_entries();
+        let mut paths = vec![];
+        let mut reorder_buffer = ReorderBuffer::new(|rating: Rating<'static>| {
+            paths.push(rating.entry().path().to_owned())
+        });
+
+        reorder_buffer.push((2, entries[2].clone(), (vec![], None)));
+        reorder_buffer.push((1, entries[1].clone(), (vec![], None)));
+        assert_eq!(2, reorder_buffer.len());
+        reorder_buffer.push((0, entries[0].clone(), (vec![], None)));
+        assert_eq!(0, reorder_buffer.len());
+        drop(reorder_buffer);
+
+        let expected: Vec<_> = entries[..3].iter().map(|e| e.path().to_owned()).collect();
+        assert_eq!(expected, paths);
+    }
+
+    #[test]
+    fn rate_entries_streaming_test() {
+        let entries = get_test_dir_entries();
+        let rules = get_builtin_rules();
+        let expected = rate_entries(&entries, &rules);
+
+        for jobs in &[1, 2, 8] {
+            let mut ratings = vec![];
+            rate_entries_streaming(entries.clone(), &rules, *jobs, |rating| {
+                ratings.push(rating)
+            });
+
+            assert_eq!(expected.len(), ratings.len());
+            for (expected, actual) in expected.iter().zip(ratings.iter()) {
+                assert_eq!(expected.entry().path(), actual.entry().path());
+                assert_eq!(expected.rating(), actual.rating());
+                assert_eq!(
+                    join_violations(expected.violations()),
+                    join_violations(actual.violations())
+                );
+            }
+        }
+    }
+
+    /// Entry whose classification panics when its file name is checked
+    #[derive(Debug)]
+    struct PanickingDirEntry(StandaloneDirEntry);
+
+    impl DirEntryTrait for PanickingDirEntry {
+        fn path(&self) -> &Path {
+            self.0.path()
+        }
+
+        fn relative_path(&self) -> &Path {
+            self.0.relative_path()
+        }
+
+        fn path_is_symlink(&self) -> bool {
+            self.0.path_is_symlink()
+        }
+
+        fn metadata(&self) -> io::Result<fs::Metadata> {
+            self.0.metadata()
+        }
+
+        fn file_type(&self) -> Box<dyn FileTypeTrait> {
+            self.0.file_type()
+        }
+
+        fn file_name(&self) -> &OsStr {
+            panic!("Classification of {} failed", self.0.path().display())
+        }
+    }
+
+    #[test]
+    fn rate_entries_streaming_worker_panic_test() {
+        let rules = vec![
+            Rule::with_path("php", Severity::MAJOR, RawPath::with_path(".php"))
+                .unwrap()
+                .with_match_on(MatchTarget::FileName),
+        ];
+        let entries: Vec<PanickingDirEntry> = get_test_dir_entries()
+            .into_iter()
+            .cycle()
+            .take(40)
+            .map(PanickingDirEntry)
+            .collect();
+
+        // The scan must not wait for the result of the panicked worker
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                rate_entries_streaming(entries, &rules, 4, |_| {})
+            }));
+            sender.send(result.is_err()).unwrap();
+        });
+
+        assert_eq!(Ok(true), receiver.recv_timeout(Duration::from_secs(10)));
+    }
+}
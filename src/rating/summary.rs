@@ -1,36 +1,41 @@
 use crate::{Rating, Severity};
+use serde::Serialize;
 
+#[derive(Default, Serialize)]
 pub struct Summary {
     critical: isize,
     major: isize,
     minor: isize,
     notice: isize,
     clean: isize,
+    partially_scanned: isize,
 }
 
 impl Summary {
     /// Build a summary of the overall Ratings
     pub fn build(ratings: &[Rating<'_>]) -> Self {
-        let mut summary = Summary {
-            critical: 0,
-            major: 0,
-            minor: 0,
-            notice: 0,
-            clean: 0,
-        };
+        let mut summary = Summary::default();
         for rating in ratings {
-            match rating.rating() {
-                r if r >= Severity::CRITICAL as isize => summary.critical += 1,
-                r if r >= Severity::MAJOR as isize => summary.major += 1,
-                r if r >= Severity::MINOR as isize => summary.minor += 1,
-                r if r >= Severity::NOTICE as isize => summary.notice += 1,
-                _ => summary.clean += 1,
-            }
+            summary.add(rating);
         }
 
         summary
     }
 
+    /// Add the Rating to the summary
+    pub fn add(&mut self, rating: &Rating<'_>) {
+        match rating.rating() {
+            r if r >= Severity::CRITICAL as isize => self.critical += 1,
+            r if r >= Severity::MAJOR as isize => self.major += 1,
+            r if r >= Severity::MINOR as isize => self.minor += 1,
+            r if r >= Severity::NOTICE as isize => self.notice += 1,
+            _ => self.clean += 1,
+        }
+        if rating.partial_scan().is_some() {
+            self.partially_scanned += 1;
+        }
+    }
+
     pub fn critical(&self) -> isize {
         self.critical
     }
@@ -51,13 +56,71 @@ impl Summary {
         self.clean
     }
 
+    /// Return the number of files whose content was only partially scanned
+    pub fn partially_scanned(&self) -> isize {
+        self.partially_scanned
+    }
+
     /// Return the number of ratings including and greater than the `severity`
     pub fn ratings_above(&self, severity: Severity) -> isize {
         match severity {
             Severity::CRITICAL => self.critical,
             Severity::MAJOR => self.critical + self.major,
             Severity::MINOR => self.critical + self.major + self.minor,
-            Severity::NOTICE | _ => self.critical + self.major + self.minor + self.notice,
+            _ => self.critical + self.major + self.minor + self.notice,
         }
     }
+
+    /// Return the highest severity of all ratings (`Severity::NONE` if all files are clean)
+    pub fn highest_severity(&self) -> Severity {
+        for severity in &[
+            Severity::CRITICAL,
+            Severity::MAJOR,
+            Severity::MINOR,
+            Severity::NOTICE,
+        ] {
+            if self.ratings_above(*severity) > 0 {
+                return *severity;
+            }
+        }
+
+        Severity::NONE
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn build_summary(critical: isize, major: isize, minor: isize, notice: isize) -> Summary {
+        Summary {
+            critical,
+            major,
+            minor,
+            notice,
+            clean: 2,
+            partially_scanned: 0,
+        }
+    }
+
+    #[test]
+    fn highest_severity_test() {
+        assert_eq!(Severity::NONE, build_summary(0, 0, 0, 0).highest_severity());
+        assert_eq!(
+            Severity::NOTICE,
+            build_summary(0, 0, 0, 3).highest_severity()
+        );
+        assert_eq!(
+            Severity::MINOR,
+            build_summary(0, 0, 1, 3).highest_severity()
+        );
+        assert_eq!(
+            Severity::MAJOR,
+            build_summary(0, 1, 0, 0).highest_severity()
+        );
+        assert_eq!(
+            Severity::CRITICAL,
+            build_summary(1, 1, 1, 1).highest_severity()
+        );
+    }
 }
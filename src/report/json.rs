@@ -0,0 +1,107 @@
+use crate::errors::*;
+use crate::{Rating, Severity, Summary};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct JsonReport<'a, 'b> {
+    min_severity: Severity,
+    summary: &'a Summary,
+    ratings: Vec<&'a Rating<'b>>,
+}
+
+/// Serialize the `summary` and all `ratings` including and above `min_severity` as JSON
+///
+/// ```json
+/// {
+///   "min_severity": "NOTICE",
+///   "summary": {
+///     "critical": 1, "major": 0, "minor": 0, "notice": 0, "clean": 3, "partially_scanned": 1
+///   },
+///   "ratings": [
+///     {
+///       "path": "/var/www/shell.php",
+///       "rating": 90,
+///       "description": "CRITICAL",
+///       "violations": [
+///         {
+///           "name": "php::content::dezmond",
+///           "severity": "CRITICAL",
+///           "matches": [{ "offset": 15, "line": 2, "column": 10, "excerpt": "Dezmond" }]
+///         }
+///       ],
+///       "partial_scan": { "scanned_bytes": 4194304, "file_size": 9437184 }
+///     }
+///   ]
+/// }
+/// ```
+pub fn to_json(
+    min_severity: Severity,
+    summary: &Summary,
+    ratings: &[Rating<'_>],
+) -> Result<String> {
+    let report = JsonReport {
+        min_severity,
+        summary,
+        ratings: ratings
+            .iter()
+            .filter(|rating| rating.rating() >= min_severity as isize)
+            .collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_helpers::get_test_dir_entry;
+    use crate::{rate_entries, RawPath, Rule};
+
+    #[test]
+    fn to_json_test() {
+        let entries = vec![
+            get_test_dir_entry("dezmond.php"),
+            get_test_dir_entry("tx_mocfilemanager.php"),
+        ];
+        let rules = vec![Rule::new(
+            "php::content::dezmond",
+            Severity::CRITICAL,
+            RawPath::with_path(".php"),
+            Some("dezmond".to_string()),
+        )
+        .unwrap()
+        .with_description("Dezmond backdoor")];
+        let ratings = rate_entries(&entries, &rules);
+        let summary = Summary::build(&ratings);
+
+        let json = to_json(Severity::NOTICE, &summary, &ratings).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!("NOTICE", value["min_severity"]);
+        assert_eq!(1, value["summary"]["critical"]);
+        assert_eq!(1, value["summary"]["clean"]);
+        assert_eq!(0, value["summary"]["partially_scanned"]);
+
+        let ratings = value["ratings"].as_array().unwrap();
+        assert_eq!(1, ratings.len());
+        assert!(ratings[0]["path"]
+            .as_str()
+            .unwrap()
+            .ends_with("/tests/resources/files/dezmond.php"));
+        assert_eq!(90, ratings[0]["rating"]);
+        assert_eq!("CRITICAL", ratings[0]["description"]);
+        assert!(ratings[0].get("partial_scan").is_none());
+        assert_eq!("php::content::dezmond", ratings[0]["violations"][0]["name"]);
+        assert_eq!("CRITICAL", ratings[0]["violations"][0]["severity"]);
+        assert_eq!(
+            "Dezmond backdoor",
+            ratings[0]["violations"][0]["rule"]["description"]
+        );
+        assert!(ratings[0]["violations"][0]["rule"].get("tags").is_none());
+        let content_match = &ratings[0]["violations"][0]["matches"][0];
+        assert_eq!(15, content_match["offset"]);
+        assert_eq!(2, content_match["line"]);
+        assert_eq!(10, content_match["column"]);
+        assert_eq!("Dezmond", content_match["excerpt"]);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::join::join_violations;
+use crate::{Rating, Severity};
+use std::fmt::Write;
+
+/// Build a JUnit XML report from the `ratings`
+///
+/// Every rated file is reported as `testcase`. Files with a rating including and above
+/// `min_severity` contain a `failure` with the names of the violated rules.
+pub fn to_junit(min_severity: Severity, ratings: &[Rating<'_>]) -> String {
+    let tests = ratings.len();
+    let failures = ratings
+        .iter()
+        .filter(|rating| is_failure(min_severity, rating))
+        .count();
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"hackscanner\" tests=\"{}\" failures=\"{}\">",
+        tests, failures
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"hackscanner\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">",
+        tests, failures
+    );
+    for rating in ratings {
+        let path_as_string = rating.entry().path().to_string_lossy();
+        if is_failure(min_severity, rating) {
+            let _ = writeln!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"hackscanner\">",
+                escape(&path_as_string)
+            );
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                escape(&join_violations(rating.violations())),
+                rating.rating_description(),
+                escape(&build_failure_text(rating))
+            );
+            xml.push_str("    </testcase>\n");
+        } else {
+            let _ = writeln!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"hackscanner\"/>",
+                escape(&path_as_string)
+            );
+        }
+    }
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>\n");
+
+    xml
+}
+
+fn build_failure_text(rating: &Rating<'_>) -> String {
+    let mut text = format!("{} (rating {})", rating, rating.rating());
+    for violation in rating.violations() {
+        for content_match in violation.matches() {
+            let _ = write!(text, "\n{}: {}", violation.name(), content_match);
+        }
+    }
+
+    text
+}
+
+fn is_failure(min_severity: Severity, rating: &Rating<'_>) -> bool {
+    rating.rating() >= min_severity as isize
+}
+
+/// Escape the XML special characters in `input`
+fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            c if (c as u32) < 0x20 && c != '\t' && c != '\n' && c != '\r' => {}
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_helpers::get_test_dir_entry;
+    use crate::{rate_entries, RawPath, Rule};
+
+    #[test]
+    fn to_junit_test() {
+        let entries = vec![
+            get_test_dir_entry("dezmond.php"),
+            get_test_dir_entry("tx_mocfilemanager.php"),
+        ];
+        let rules = vec![
+            Rule::new(
+                "php::content::dezmond",
+                Severity::CRITICAL,
+                RawPath::with_path(".php"),
+                Some("dezmond".to_string()),
+            )
+            .unwrap(),
+            Rule::new(
+                "<error_reporting>",
+                Severity::NOTICE,
+                RawPath::with_path(".php"),
+                Some(r"error_reporting\(".to_string()),
+            )
+            .unwrap(),
+        ];
+        let ratings = rate_entries(&entries, &rules);
+
+        let xml = to_junit(Severity::NOTICE, &ratings);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert!(xml.contains("<testsuites name=\"hackscanner\" tests=\"2\" failures=\"1\">"));
+        assert!(xml.contains(
+            "<failure message=\"php::content::dezmond, &lt;error_reporting&gt;\" type=\"CRITICAL\">"
+        ));
+        assert!(xml.contains("\nphp::content::dezmond: line 2, column 10: Dezmond"));
+        assert!(xml.contains(
+            "/tests/resources/files/tx_mocfilemanager.php\" classname=\"hackscanner\"/>"
+        ));
+    }
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(
+            "a &amp; b &lt;c&gt; &quot;d&quot; &apos;e&apos;",
+            escape("a & b <c> \"d\" 'e'")
+        );
+        assert_eq!("ab", escape("a\u{1}b"));
+    }
+}
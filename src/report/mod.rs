@@ -0,0 +1,59 @@
+//! Report
+//!
+//! Machine-readable representations of the scan results. The human readable output is built by
+//! the binary's `ui` module.
+#[cfg(feature = "json")]
+mod json;
+mod junit;
+#[cfg(feature = "json")]
+mod sarif;
+
+#[cfg(feature = "json")]
+pub use self::json::to_json;
+pub use self::junit::to_junit;
+#[cfg(feature = "json")]
+pub use self::sarif::to_sarif;
+
+use crate::errors::*;
+use std::str::FromStr;
+
+/// Output format of the scan results
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Format {
+    Text,
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "json")]
+    Sarif,
+    Junit,
+}
+
+impl Format {
+    /// Return the names of all available formats
+    pub fn names() -> &'static [&'static str] {
+        &[
+            "text",
+            #[cfg(feature = "json")]
+            "json",
+            #[cfg(feature = "json")]
+            "sarif",
+            "junit",
+        ]
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            #[cfg(feature = "json")]
+            "json" => Ok(Format::Json),
+            #[cfg(feature = "json")]
+            "sarif" => Ok(Format::Sarif),
+            "junit" => Ok(Format::Junit),
+            _ => bail!("No output format '{}'", s),
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use crate::classifier::ContentMatch;
+use crate::errors::*;
+use crate::{Rating, Rule, RuleTrait, Severity};
+use serde::Serialize;
+use std::path::Path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    /// `ContentMatch::column` counts characters, not the default UTF-16 code units
+    column_kind: &'static str,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: String,
+    name: String,
+    short_description: Message,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    default_configuration: ReportingConfiguration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<PropertyBag>,
+}
+
+#[derive(Serialize)]
+struct PropertyBag {
+    tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ReportingConfiguration {
+    level: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_column: Option<usize>,
+    byte_offset: usize,
+    snippet: Message,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+/// Build a SARIF 2.1.0 log from the `rules` and all `ratings` including and above `min_severity`
+///
+/// Each `Rule` is mapped to a `reportingDescriptor` (rules sharing an ID are reported once) and
+/// each `Violation` of a matching `Rating` is mapped to a `result`.
+pub fn to_sarif(min_severity: Severity, rules: &[Rule], ratings: &[Rating<'_>]) -> Result<String> {
+    let descriptors = build_descriptors(rules);
+
+    let mut results = vec![];
+    for rating in ratings {
+        if rating.rating() < min_severity as isize {
+            continue;
+        }
+        let uri = path_to_uri(rating.entry().path());
+        for violation in rating.violations() {
+            results.push(SarifResult {
+                rule_id: violation.id().to_owned(),
+                rule_index: descriptors.iter().position(|d| d.id == violation.id()),
+                level: level_for_severity(violation.severity()),
+                message: Message {
+                    text: format!(
+                        "File violates rule '{}' (severity {}, file rating {})",
+                        violation.name(),
+                        violation.severity(),
+                        rating.rating_description()
+                    ),
+                },
+                locations: build_locations(&uri, violation.matches()),
+            });
+        }
+    }
+
+    let log = SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "hackscanner",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: "https://github.com/cundd/hackscanner",
+                    rules: descriptors,
+                },
+            },
+            column_kind: "unicodeCodePoints",
+            results,
+        }],
+    };
+
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+/// Build one location per content match, or a single location without region for path matches
+fn build_locations(uri: &str, matches: &[ContentMatch]) -> Vec<Location> {
+    let build_location = |region: Option<Region>| Location {
+        physical_location: PhysicalLocation {
+            artifact_location: ArtifactLocation {
+                uri: uri.to_owned(),
+            },
+            region,
+        },
+    };
+    if matches.is_empty() {
+        return vec![build_location(None)];
+    }
+
+    matches
+        .iter()
+        .map(|content_match| {
+            build_location(Some(Region {
+                start_line: content_match.line(),
+                start_column: content_match.column(),
+                byte_offset: content_match.offset(),
+                snippet: Message {
+                    text: content_match.excerpt().to_owned(),
+                },
+            }))
+        })
+        .collect()
+}
+
+fn build_descriptors(rules: &[Rule]) -> Vec<ReportingDescriptor> {
+    let mut descriptors: Vec<ReportingDescriptor> = Vec::with_capacity(rules.len());
+    for rule in rules {
+        if descriptors.iter().any(|d| d.id == rule.id()) {
+            continue;
+        }
+        descriptors.push(ReportingDescriptor {
+            id: rule.id().to_owned(),
+            name: rule.name().to_owned(),
+            short_description: Message {
+                text: format!("{} (severity {})", rule.name(), rule.severity()),
+            },
+            full_description: rule.description().map(|description| Message {
+                text: description.to_owned(),
+            }),
+            help_uri: rule
+                .references()
+                .iter()
+                .find(|reference| {
+                    reference.starts_with("https://") || reference.starts_with("http://")
+                })
+                .cloned(),
+            default_configuration: ReportingConfiguration {
+                level: level_for_severity(rule.severity()),
+            },
+            properties: if rule.tags().is_empty() {
+                None
+            } else {
+                Some(PropertyBag {
+                    tags: rule.tags().to_vec(),
+                })
+            },
+        });
+    }
+
+    descriptors
+}
+
+/// Map the `Severity` to a SARIF level
+fn level_for_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::CRITICAL | Severity::MAJOR => "error",
+        Severity::MINOR => "warning",
+        Severity::NOTICE => "note",
+        _ => "none",
+    }
+}
+
+/// Build a URI reference for the given path
+///
+/// Absolute paths are transformed into `file://` URIs, relative paths are kept relative
+fn path_to_uri(path: &Path) -> String {
+    let path_as_string = path.to_string_lossy();
+    let mut uri = String::with_capacity(path_as_string.len() + 7);
+    if path.is_absolute() {
+        uri.push_str("file://");
+    }
+    for byte in path_as_string.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_helpers::get_test_dir_entry;
+    use crate::{rate_entries, RawPath, RuleMetadata};
+
+    #[test]
+    fn to_sarif_test() {
+        let entries = vec![
+            get_test_dir_entry("dezmond.php"),
+            get_test_dir_entry("tx_mocfilemanager.php"),
+        ];
+        let rules = vec![
+            Rule::new(
+                "php::content::dezmond",
+                Severity::CRITICAL,
+                RawPath::with_path(".php"),
+                Some("dezmond".to_string()),
+            )
+            .unwrap()
+            .with_metadata(RuleMetadata {
+                id: Some("HS0001".to_string()),
+                description: Some("Dezmond backdoor".to_string()),
+                references: vec!["https://example.com/dezmond".to_string()],
+                tags: vec!["backdoor".to_string()],
+                author: None,
+            }),
+            Rule::new(
+                "php::content::error_reporting",
+                Severity::NOTICE,
+                RawPath::with_path(".php"),
+                Some(r"error_reporting\(".to_string()),
+            )
+            .unwrap(),
+            Rule::new(
+                "php::content::dezmond",
+                Severity::CRITICAL,
+                RawPath::with_path(".php"),
+                Some("raprap1".to_string()),
+            )
+            .unwrap(),
+        ];
+        let ratings = rate_entries(&entries, &rules);
+
+        let sarif = to_sarif(Severity::NOTICE, &rules, &ratings).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&sarif).unwrap();
+
+        assert_eq!("2.1.0", value["version"]);
+        let run = &value["runs"][0];
+        assert_eq!("hackscanner", run["tool"]["driver"]["name"]);
+        assert_eq!("unicodeCodePoints", run["columnKind"]);
+
+        let descriptors = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(3, descriptors.len());
+        assert_eq!("HS0001", descriptors[0]["id"]);
+        assert_eq!("php::content::dezmond", descriptors[0]["name"]);
+        assert_eq!(
+            "Dezmond backdoor",
+            descriptors[0]["fullDescription"]["text"]
+        );
+        assert_eq!("https://example.com/dezmond", descriptors[0]["helpUri"]);
+        assert_eq!("backdoor", descriptors[0]["properties"]["tags"][0]);
+        assert_eq!("error", descriptors[0]["defaultConfiguration"]["level"]);
+        assert_eq!("php::content::error_reporting", descriptors[1]["id"]);
+        assert_eq!("note", descriptors[1]["defaultConfiguration"]["level"]);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(3, results.len());
+        assert_eq!("HS0001", results[0]["ruleId"]);
+        assert_eq!(0, results[0]["ruleIndex"]);
+        assert_eq!("error", results[0]["level"]);
+        let uri = results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+            .as_str()
+            .unwrap();
+        assert!(uri.starts_with("file:///"));
+        assert!(uri.ends_with("/tests/resources/files/dezmond.php"));
+        let region = &results[0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(2, region["startLine"]);
+        assert_eq!(10, region["startColumn"]);
+        assert_eq!("Dezmond", region["snippet"]["text"]);
+    }
+
+    #[test]
+    fn build_locations_without_line_test() {
+        let content = b"eval($x);";
+        let regex = regex::bytes::Regex::new("eval").unwrap();
+        let captures = regex.captures(content).unwrap();
+        // Matches in the tail of a file have no line and column
+        let content_match =
+            ContentMatch::from_captures(content, &regex, &captures).shifted(1024, None);
+
+        let locations = build_locations("shell.php", &[content_match]);
+        let value = serde_json::to_value(&locations).unwrap();
+        let region = &value[0]["physicalLocation"]["region"];
+
+        assert_eq!(1024, region["byteOffset"]);
+        assert!(region.get("startLine").is_none());
+        assert!(region.get("startColumn").is_none());
+    }
+
+    #[test]
+    fn path_to_uri_test() {
+        assert_eq!("some/file.php", path_to_uri(Path::new("some/file.php")));
+        assert_eq!(
+            "file:///var/www/some%20file.php",
+            path_to_uri(Path::new("/var/www/some file.php"))
+        );
+    }
+}
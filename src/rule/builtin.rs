@@ -1,260 +1,152 @@
-use crate::rule::raw_rule::RawPath;
-use crate::severity::Severity;
+use crate::errors::*;
+use crate::rule::reader::list_rule_files;
+use crate::rule::rule_pack::RulePack;
 use crate::Rule as PatternRule;
+use lazy_static::lazy_static;
+use std::path::Path;
+
+/// Rule packs compiled into the binary (file name and content)
+const EMBEDDED_PACKS: [(&str, &str); 2] = [
+    ("php.yaml", include_str!("packs/php.yaml")),
+    ("typo3.yaml", include_str!("packs/typo3.yaml")),
+];
+
+lazy_static! {
+    /// Embedded rule packs, parsed (and their patterns compiled) on first use
+    static ref EMBEDDED_RULE_PACKS: Vec<RulePack> = EMBEDDED_PACKS
+        .iter()
+        .map(|(file_name, content)| {
+            RulePack::from_embedded_yaml(content)
+                .unwrap_or_else(|e| panic!("Embedded rule pack '{}' is invalid: {}", file_name, e))
+        })
+        .collect();
+}
 
 pub fn get_builtin_rules() -> Vec<PatternRule> {
-    let mut collection = vec![];
+    EMBEDDED_RULE_PACKS
+        .iter()
+        .flat_map(|pack| pack.rules().iter().cloned())
+        .collect()
+}
 
-    collection.append(&mut get_builtin_rules_php());
-    collection.append(&mut get_builtin_rules_typo3());
+/// Return the rule packs compiled into the binary
+pub fn get_embedded_rule_packs() -> Vec<RulePack> {
+    EMBEDDED_RULE_PACKS.clone()
+}
+
+/// Return the embedded rule packs, updated with the packs from the given files and directories
+///
+/// A pack replaces the embedded (or previously read) pack with the same name if its version is
+/// higher. Packs with a new name are appended
+pub fn get_builtin_rule_packs<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<RulePack>> {
+    let mut packs = get_embedded_rule_packs();
+    for path in paths {
+        let path = path.as_ref();
+        let files = if path.is_dir() {
+            list_rule_files(path)?
+        } else {
+            vec![path.to_owned()]
+        };
+        for file in files {
+            merge_rule_pack(&mut packs, RulePack::from_file(&file)?);
+        }
+    }
+
+    Ok(packs)
+}
 
-    collection
+/// Return the builtin rules of the packs returned by [`get_builtin_rule_packs`]
+pub fn get_builtin_rules_from_packs<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<PatternRule>> {
+    Ok(get_builtin_rule_packs(paths)?
+        .into_iter()
+        .flat_map(RulePack::into_rules)
+        .collect())
 }
 
-fn get_builtin_rules_php() -> Vec<PatternRule> {
-    vec![
-        PatternRule::with_path_and_content(
-            "php::content::error_reporting",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            r"error_reporting\(",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::preg_",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            "='preg_",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::preg_",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            r#"="preg_"#,
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::eval",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            r"\beval\(",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::gzinflate",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            r"\bgzinflate\(",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::@gzinflate",
-            Severity::MINOR,
-            RawPath::with_path(".php"),
-            r"\b@gzinflate\(",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::substr(MD5(strrev",
-            Severity::MINOR,
-            RawPath::with_path(".php"),
-            r"substr\(MD5\(strrev",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::system",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            r"\bsystem\(",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::exec",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            r"\bexec\(",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::create_function",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            r"\bcreate_function\(",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::return",
-            Severity::MAJOR,
-            RawPath::with_path(".php"),
-            r#"","\."\);return;?>"#,
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::base64_decode",
-            Severity::MAJOR,
-            RawPath::with_path(".php"),
-            r"eval\(base64_decode\(",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::6fbcb8b698317491a5fd7926f2c3b7de",
-            Severity::CRITICAL,
-            RawPath::with_path(".php"),
-            "6fbcb8b698317491a5fd7926f2c3b7de",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::4ngel",
-            Severity::CRITICAL,
-            RawPath::with_path(".php"),
-            r"Codz by angel\(4ngel\)",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::dezmond",
-            Severity::CRITICAL,
-            RawPath::with_path(".php"),
-            "dezmond",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::FilesMan",
-            Severity::CRITICAL,
-            RawPath::with_path(".php"),
-            "FilesMan",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::raprap1",
-            Severity::CRITICAL,
-            RawPath::with_path(".php"),
-            "raprap1",
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "php::file::tx_mocfilemanager",
-            Severity::CRITICAL,
-            RawPath::with_path("tx_mocfilemanager.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "php::file::cache",
-            Severity::CRITICAL,
-            RawPath::with_regex(r"/cache\.dat"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "php::file::cache",
-            Severity::CRITICAL,
-            RawPath::with_regex(r"/\.cache\.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "php::file::ext_fpdf",
-            Severity::CRITICAL,
-            RawPath::with_regex("ext_fpdf.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "php::file::ixwstat",
-            Severity::CRITICAL,
-            RawPath::with_regex("ixwstat.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::cialis",
-            Severity::NOTICE,
-            RawPath::with_path(".php"),
-            "='preg_",
-        )
-        .unwrap(),
-        PatternRule::with_path_and_content(
-            "php::content::next1.space",
-            Severity::CRITICAL,
-            RawPath::with_path(".php"),
-            "next1.space",
-        )
-        .unwrap(),
-    ]
+fn merge_rule_pack(packs: &mut Vec<RulePack>, pack: RulePack) {
+    let source = pack.source().map(|s| s.display().to_string());
+    let source = source.unwrap_or_default();
+    match packs.iter_mut().find(|p| p.name() == pack.name()) {
+        Some(current) if current.version() < pack.version() => {
+            info!(
+                "Replace rule pack '{}' version {} with version {} from '{}'",
+                current.name(),
+                current.version(),
+                pack.version(),
+                source
+            );
+            *current = pack;
+        }
+        Some(current) => warn!(
+            "Ignore rule pack '{}' version {} from '{}', because version {} is already loaded",
+            pack.name(),
+            pack.version(),
+            source,
+            current.version()
+        ),
+        None => {
+            info!(
+                "Add rule pack '{}' version {} from '{}'",
+                pack.name(),
+                pack.version(),
+                source
+            );
+            packs.push(pack);
+        }
+    }
 }
 
-fn get_builtin_rules_typo3() -> Vec<PatternRule> {
-    vec![
-        PatternRule::with_path_and_content(
-            "typo3::file::php-in-typo3conf-wordpress",
-            Severity::NOTICE,
-            RawPath::with_regex("typo3conf/.*\\.php"),
-            "wordpress",
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-typo3conf-static_info_tables",
-            Severity::MAJOR,
-            RawPath::with_path("typo3conf/ext/static_info_tables/Classes/static_info_tables.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-fileadmin",
-            Severity::MINOR,
-            RawPath::with_regex(r"fileadmin/.*\.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-l10n",
-            Severity::MAJOR,
-            RawPath::with_regex(r"typo3conf/l10n/.*\.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-typo3temp",
-            Severity::MINOR,
-            RawPath::with_regex(r"typo3temp/.*\.php$"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-typo3temp-Cache",
-            Severity::WHITELIST,
-            RawPath::with_regex(r"typo3temp/(var/)?Cache/.*\.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-typo3temp-autoload",
-            Severity::WHITELIST,
-            RawPath::with_regex(r"typo3temp/autoload/autoload_[^/]*\.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-typo3temp-autoload-test",
-            Severity::WHITELIST,
-            RawPath::with_regex(r"typo3temp/autoload-tests/autoload_[^/]*\.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-typo3temp-extupdate",
-            Severity::WHITELIST,
-            RawPath::with_regex(
-                r"typo3temp/ExtensionManager/UpdateScripts/ext_update[a-z0-9]*\.php",
-            ),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-typo3temp-extupdate",
-            Severity::WHITELIST,
-            RawPath::with_regex(r"typo3temp/var/transient/ext_update[a-z0-9]*\.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-uploads",
-            Severity::MAJOR,
-            RawPath::with_regex(r"/uploads/.*\.php"),
-        )
-        .unwrap(),
-        PatternRule::with_path(
-            "typo3::file::php-in-extension-builder-backups",
-            Severity::EASE2X, // Decrease ("ease") the rating form MAJOR to NOTICE
-            RawPath::with_regex(r"/uploads/tx_extensionbuilder/backups/.*\.php"),
-        )
-        .unwrap(),
-    ]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rule::RuleTrait;
+    use crate::Severity;
+    use std::path::PathBuf;
+
+    fn packs_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/rules/packs")
+    }
+
+    #[test]
+    fn get_embedded_rule_packs_test() {
+        let packs = get_embedded_rule_packs();
+        let names: Vec<&str> = packs.iter().map(RulePack::name).collect();
+        assert_eq!(vec!["php", "typo3"], names);
+        assert!(packs.iter().all(|p| p.source().is_none()));
+
+        let rules = get_builtin_rules();
+        assert_eq!(
+            rules.len(),
+            packs.iter().map(|p| p.rules().len()).sum::<usize>()
+        );
+        assert!(rules.iter().all(|r| r.source().is_none()));
+    }
+
+    #[test]
+    fn get_builtin_rule_packs_test() {
+        let packs = get_builtin_rule_packs(&[packs_dir()]).unwrap();
+        let summary: Vec<(&str, u64)> = packs.iter().map(|p| (p.name(), p.version())).collect();
+        // The outdated typo3 pack is ignored, the unknown extra pack is appended
+        #[cfg(feature = "json")]
+        assert_eq!(vec![("php", 2), ("typo3", 1), ("extra", 1)], summary);
+        #[cfg(not(feature = "json"))]
+        assert_eq!(vec![("php", 2), ("typo3", 1)], summary);
+
+        assert_eq!(1, packs[0].rules().len());
+        assert_eq!(Severity::MAJOR, packs[0].rules()[0].severity());
+        assert!(packs[1].source().is_none());
+    }
+
+    #[test]
+    fn get_builtin_rules_from_packs_test() {
+        let rules = get_builtin_rules_from_packs(&[packs_dir().join("php.yaml")]).unwrap();
+        assert!(!rules.iter().any(|r| r.name() == "php::content::system"));
+        assert!(rules.iter().any(|r| r.name().starts_with("typo3::")));
+
+        assert!(get_builtin_rules_from_packs(&[packs_dir().join("missing.yaml")]).is_err());
+        assert_eq!(
+            get_builtin_rules(),
+            get_builtin_rules_from_packs::<&Path>(&[]).unwrap()
+        );
+    }
 }
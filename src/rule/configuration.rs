@@ -0,0 +1,147 @@
+use crate::rule::pattern_rule::PatternRule;
+use crate::rule::RuleTrait;
+use crate::Severity;
+use serde::Deserialize;
+
+/// Rules and changes of the builtin rules read from configuration files
+#[derive(Debug, Default)]
+pub struct Configuration {
+    /// Custom rules
+    pub rules: Vec<PatternRule>,
+    /// Changes to apply to the builtin rules
+    pub builtin_changes: BuiltinChanges,
+}
+
+/// Changes of the builtin rules defined with `disable` and `override` directives
+#[derive(Debug, Default)]
+pub struct BuiltinChanges {
+    /// Names or prefixes (ending with `*`) of the builtin rules to disable
+    pub disable: Vec<String>,
+    /// Severities to override
+    pub overrides: Vec<SeverityOverride>,
+}
+
+/// Severity to use for the builtin rules matching `name`
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SeverityOverride {
+    /// Name or prefix (ending with `*`) of the builtin rules
+    pub name: String,
+    /// The new severity
+    pub severity: Severity,
+}
+
+impl BuiltinChanges {
+    pub fn is_empty(&self) -> bool {
+        self.disable.is_empty() && self.overrides.is_empty()
+    }
+
+    /// Remove the disabled rules and override the severities of the `builtin_rules`
+    pub fn apply(&self, builtin_rules: Vec<PatternRule>) -> Vec<PatternRule> {
+        for pattern in &self.disable {
+            if !builtin_rules
+                .iter()
+                .any(|r| matches_name(pattern, r.name()))
+            {
+                warn!(
+                    "Disabled rule '{}' does not match any builtin rule",
+                    pattern
+                );
+            }
+        }
+        for severity_override in &self.overrides {
+            if !builtin_rules
+                .iter()
+                .any(|r| matches_name(&severity_override.name, r.name()))
+            {
+                warn!(
+                    "Overridden rule '{}' does not match any builtin rule",
+                    severity_override.name
+                );
+            }
+        }
+
+        builtin_rules
+            .into_iter()
+            .filter(|rule| {
+                !self
+                    .disable
+                    .iter()
+                    .any(|pattern| matches_name(pattern, rule.name()))
+            })
+            .map(|rule| {
+                // The last matching override wins
+                match self
+                    .overrides
+                    .iter()
+                    .rev()
+                    .find(|o| matches_name(&o.name, rule.name()))
+                {
+                    Some(severity_override) => rule.with_severity(severity_override.severity),
+                    None => rule,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Return if the rule `name` matches `pattern`
+///
+/// A `pattern` ending with `*` matches all names starting with the text before the `*`. Other
+/// patterns must be equal to the name.
+pub fn matches_name(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rule::get_builtin_rules;
+
+    #[test]
+    fn matches_name_test() {
+        assert!(matches_name("typo3::*", "typo3::file::php-in-fileadmin"));
+        assert!(matches_name("*", "php::content::eval"));
+        assert!(matches_name("php::content::eval", "php::content::eval"));
+        assert!(!matches_name(
+            "php::content::eval",
+            "php::content::evaluate"
+        ));
+        assert!(!matches_name("typo3::*", "php::content::eval"));
+    }
+
+    #[test]
+    fn apply_test() {
+        let builtin_rules = get_builtin_rules();
+        let changes = BuiltinChanges {
+            disable: vec!["typo3::*".to_owned(), "php::content::system".to_owned()],
+            overrides: vec![
+                SeverityOverride {
+                    name: "php::content::eval".to_owned(),
+                    severity: Severity::MAJOR,
+                },
+                SeverityOverride {
+                    name: "php::content::exec".to_owned(),
+                    severity: Severity::MINOR,
+                },
+                SeverityOverride {
+                    name: "php::content::e*".to_owned(),
+                    severity: Severity::CRITICAL,
+                },
+            ],
+        };
+
+        let rules = changes.apply(builtin_rules.clone());
+
+        assert!(rules.len() < builtin_rules.len());
+        assert!(!rules.iter().any(|r| r.name().starts_with("typo3::")));
+        assert!(!rules.iter().any(|r| r.name() == "php::content::system"));
+        let severity_of = |name: &str| rules.iter().find(|r| r.name() == name).unwrap().severity();
+        assert_eq!(Severity::CRITICAL, severity_of("php::content::eval"));
+        assert_eq!(Severity::CRITICAL, severity_of("php::content::exec"));
+        assert_eq!(Severity::NOTICE, severity_of("php::content::gzinflate"));
+    }
+}
@@ -0,0 +1,259 @@
+use regex::bytes::Regex;
+use std::fmt;
+
+/// Compiled content condition of a Rule
+///
+/// The patterns are matched against the raw bytes of the file content
+#[derive(Debug, Clone)]
+pub enum ContentPattern {
+    /// A single regular expression
+    Regex(Regex),
+    /// A boolean combination of content patterns
+    Group(ContentGroup),
+}
+
+/// Compiled group of content patterns
+///
+/// The group matches if every pattern in `all`, at least one pattern in `any` (if not empty) and
+/// no pattern in `none` matches
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentGroup {
+    all: Vec<ContentPattern>,
+    any: Vec<ContentPattern>,
+    none: Vec<ContentPattern>,
+}
+
+impl ContentPattern {
+    /// Return if the `content` matches the pattern
+    pub fn is_match<C: AsRef<[u8]>>(&self, content: C) -> bool {
+        self.is_match_bytes(content.as_ref())
+    }
+
+    fn is_match_bytes(&self, content: &[u8]) -> bool {
+        match self {
+            ContentPattern::Regex(regex) => regex.is_match(content),
+            ContentPattern::Group(group) => group.is_match(content),
+        }
+    }
+
+    /// Return the regular expressions that must match for the pattern to match
+    ///
+    /// Patterns in `none` lists are not returned, as they never produce a match
+    pub fn positive_regexes(&self) -> Vec<&Regex> {
+        match self {
+            ContentPattern::Regex(regex) => vec![regex],
+            ContentPattern::Group(group) => group
+                .all
+                .iter()
+                .chain(group.any.iter())
+                .flat_map(|pattern| pattern.positive_regexes())
+                .collect(),
+        }
+    }
+
+    /// Return all regular expressions of the pattern and if they must match (`false` for the
+    /// patterns in `none` lists)
+    pub fn regexes(&self) -> Vec<(&Regex, bool)> {
+        let mut regexes = vec![];
+        self.collect_regexes(true, &mut regexes);
+
+        regexes
+    }
+
+    fn collect_regexes<'a>(&'a self, positive: bool, regexes: &mut Vec<(&'a Regex, bool)>) {
+        match self {
+            ContentPattern::Regex(regex) => regexes.push((regex, positive)),
+            ContentPattern::Group(group) => {
+                for pattern in group.all.iter().chain(group.any.iter()) {
+                    pattern.collect_regexes(positive, regexes);
+                }
+                for pattern in &group.none {
+                    pattern.collect_regexes(false, regexes);
+                }
+            }
+        }
+    }
+
+    /// Return if the pattern matches, given which of the [`regexes`](Self::regexes) matched
+    /// anywhere in the content (in the same order)
+    ///
+    /// This allows to check a pattern against content that is read in multiple parts
+    pub fn is_match_with_hits(&self, hits: &[bool]) -> bool {
+        self.evaluate(&mut hits.iter().copied())
+    }
+
+    fn evaluate<I: Iterator<Item = bool>>(&self, hits: &mut I) -> bool {
+        match self {
+            ContentPattern::Regex(_) => hits.next().unwrap_or(false),
+            ContentPattern::Group(group) => {
+                // Every pattern has to be evaluated to consume its hits
+                let all: Vec<bool> = group.all.iter().map(|p| p.evaluate(hits)).collect();
+                let any: Vec<bool> = group.any.iter().map(|p| p.evaluate(hits)).collect();
+                let none: Vec<bool> = group.none.iter().map(|p| p.evaluate(hits)).collect();
+
+                all.iter().all(|hit| *hit)
+                    && (any.is_empty() || any.iter().any(|hit| *hit))
+                    && !none.iter().any(|hit| *hit)
+            }
+        }
+    }
+}
+
+impl ContentGroup {
+    pub(crate) fn new(
+        all: Vec<ContentPattern>,
+        any: Vec<ContentPattern>,
+        none: Vec<ContentPattern>,
+    ) -> Self {
+        Self { all, any, none }
+    }
+
+    /// Return the patterns that all must match
+    pub fn all(&self) -> &[ContentPattern] {
+        &self.all
+    }
+
+    /// Return the patterns of which at least one must match
+    pub fn any(&self) -> &[ContentPattern] {
+        &self.any
+    }
+
+    /// Return the patterns that must not match
+    pub fn none(&self) -> &[ContentPattern] {
+        &self.none
+    }
+
+    fn is_match(&self, content: &[u8]) -> bool {
+        self.all
+            .iter()
+            .all(|pattern| pattern.is_match_bytes(content))
+            && (self.any.is_empty()
+                || self
+                    .any
+                    .iter()
+                    .any(|pattern| pattern.is_match_bytes(content)))
+            && !self
+                .none
+                .iter()
+                .any(|pattern| pattern.is_match_bytes(content))
+    }
+}
+
+impl PartialEq for ContentPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ContentPattern::Regex(a), ContentPattern::Regex(b)) => a.as_str() == b.as_str(),
+            (ContentPattern::Group(a), ContentPattern::Group(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ContentPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentPattern::Regex(regex) => write!(f, "{}", regex),
+            ContentPattern::Group(group) => write!(f, "{}", group),
+        }
+    }
+}
+
+impl fmt::Display for ContentGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (operator, patterns) in &[("all", &self.all), ("any", &self.any), ("none", &self.none)]
+        {
+            if patterns.is_empty() {
+                continue;
+            }
+            if !first {
+                write!(f, " ")?;
+            }
+            first = false;
+            let joined: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+            write!(f, "{}({})", operator, joined.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn regex(pattern: &str) -> ContentPattern {
+        ContentPattern::Regex(Regex::new(pattern).unwrap())
+    }
+
+    #[test]
+    fn is_match_test() {
+        let pattern = ContentPattern::Group(ContentGroup::new(
+            vec![regex("base64_decode"), regex("eval")],
+            vec![],
+            vec![regex("@license")],
+        ));
+        assert!(pattern.is_match("eval(base64_decode('...'))"));
+        assert!(!pattern.is_match("eval('...')"));
+        assert!(!pattern.is_match("/* @license MIT */ eval(base64_decode('...'))"));
+
+        let pattern = ContentPattern::Group(ContentGroup::new(
+            vec![],
+            vec![regex("='preg_"), regex("=\"preg_")],
+            vec![],
+        ));
+        assert!(pattern.is_match("$a='preg_replace'"));
+        assert!(pattern.is_match("$a=\"preg_replace\""));
+        assert!(!pattern.is_match("preg_replace()"));
+
+        let pattern =
+            ContentPattern::Group(ContentGroup::new(vec![], vec![], vec![regex("<\\?php")]));
+        assert!(pattern.is_match("no code here"));
+        assert!(!pattern.is_match("<?php echo 1;"));
+    }
+
+    #[test]
+    fn is_match_with_hits_test() {
+        let pattern = ContentPattern::Group(ContentGroup::new(
+            vec![regex("base64_decode"), regex("eval")],
+            vec![],
+            vec![ContentPattern::Group(ContentGroup::new(
+                vec![],
+                vec![regex("@license"), regex("@copyright")],
+                vec![],
+            ))],
+        ));
+        let kinds: Vec<(&str, bool)> = pattern
+            .regexes()
+            .into_iter()
+            .map(|(regex, positive)| (regex.as_str(), positive))
+            .collect();
+        assert_eq!(
+            vec![
+                ("base64_decode", true),
+                ("eval", true),
+                ("@license", false),
+                ("@copyright", false)
+            ],
+            kinds
+        );
+
+        assert!(pattern.is_match_with_hits(&[true, true, false, false]));
+        assert!(!pattern.is_match_with_hits(&[true, false, false, false]));
+        assert!(!pattern.is_match_with_hits(&[true, true, false, true]));
+    }
+
+    #[test]
+    fn display_test() {
+        let pattern = ContentPattern::Group(ContentGroup::new(
+            vec![regex("a"), regex("b")],
+            vec![],
+            vec![ContentPattern::Group(ContentGroup::new(
+                vec![],
+                vec![regex("c"), regex("d")],
+                vec![],
+            ))],
+        ));
+        assert_eq!("all(a, b) none(any(c, d))", pattern.to_string());
+    }
+}
@@ -0,0 +1,383 @@
+use super::raw_file_condition::RawFileCondition;
+use super::read_limit::format_byte_size;
+use crate::errors::*;
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::fmt;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Metadata of a file that is checked against a [`FileCondition`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStat {
+    pub size: u64,
+    /// Permission bits (including setuid, setgid and sticky bit)
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    /// Time of the last modification of the content
+    pub modified: SystemTime,
+    /// Time of the last change of the content or the inode (permissions, owner, ...)
+    pub changed: SystemTime,
+}
+
+impl FileStat {
+    pub fn from_metadata(metadata: &fs::Metadata) -> Self {
+        FileStat {
+            size: metadata.len(),
+            mode: metadata.mode() & 0o7777,
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+            modified: timestamp(metadata.mtime(), metadata.mtime_nsec()),
+            changed: timestamp(metadata.ctime(), metadata.ctime_nsec()),
+        }
+    }
+}
+
+fn timestamp(seconds: i64, nanoseconds: i64) -> SystemTime {
+    if seconds >= 0 {
+        UNIX_EPOCH + Duration::new(seconds as u64, nanoseconds as u32)
+    } else {
+        UNIX_EPOCH
+    }
+}
+
+/// Conditions on the size, permissions, owner and timestamps of a file
+///
+/// All defined conditions have to be fulfilled. The owner and group names of the
+/// [`RawFileCondition`] are resolved to ids when the condition is built
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileCondition {
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub world_writable: Option<bool>,
+    pub executable: Option<bool>,
+    pub setuid: Option<bool>,
+    pub owner: Option<u32>,
+    pub group: Option<u32>,
+    pub modified_within: Option<Duration>,
+    pub modified_older_than: Option<Duration>,
+    pub changed_within: Option<Duration>,
+    pub changed_older_than: Option<Duration>,
+}
+
+impl FileCondition {
+    /// Return if the file described by `stat` fulfills all conditions at the time `now`
+    pub fn matches(&self, stat: &FileStat, now: SystemTime) -> bool {
+        let is_set = |bits: u32| stat.mode & bits != 0;
+        // Timestamps in the future have an age of zero
+        let age = |time: SystemTime| now.duration_since(time).unwrap_or_default();
+
+        self.min_size.is_none_or(|min| stat.size >= min)
+            && self.max_size.is_none_or(|max| stat.size <= max)
+            && self
+                .world_writable
+                .is_none_or(|expected| is_set(0o002) == expected)
+            && self
+                .executable
+                .is_none_or(|expected| is_set(0o111) == expected)
+            && self
+                .setuid
+                .is_none_or(|expected| is_set(0o4000) == expected)
+            && self.owner.is_none_or(|uid| stat.uid == uid)
+            && self.group.is_none_or(|gid| stat.gid == gid)
+            && self
+                .modified_within
+                .is_none_or(|max| age(stat.modified) <= max)
+            && self
+                .modified_older_than
+                .is_none_or(|min| age(stat.modified) > min)
+            && self
+                .changed_within
+                .is_none_or(|max| age(stat.changed) <= max)
+            && self
+                .changed_older_than
+                .is_none_or(|min| age(stat.changed) > min)
+    }
+}
+
+impl TryFrom<&RawFileCondition> for FileCondition {
+    type Error = Error;
+
+    fn try_from(raw: &RawFileCondition) -> Result<Self, Self::Error> {
+        if let (Some(min), Some(max)) = (raw.min_size, raw.max_size) {
+            if min > max {
+                bail!(
+                    "The minimum size ({}) must not be greater than the maximum size ({})",
+                    format_byte_size(min),
+                    format_byte_size(max)
+                );
+            }
+        }
+
+        Ok(FileCondition {
+            min_size: raw.min_size,
+            max_size: raw.max_size,
+            world_writable: raw.world_writable,
+            executable: raw.executable,
+            setuid: raw.setuid,
+            owner: raw.owner.as_deref().map(resolve_user).transpose()?,
+            group: raw.group.as_deref().map(resolve_group).transpose()?,
+            modified_within: raw.modified_within.map(Duration::from_secs),
+            modified_older_than: raw.modified_older_than.map(Duration::from_secs),
+            changed_within: raw.changed_within.map(Duration::from_secs),
+            changed_older_than: raw.changed_older_than.map(Duration::from_secs),
+        })
+    }
+}
+
+impl fmt::Display for FileCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = |name: &str, value: bool| {
+            if value {
+                name.to_owned()
+            } else {
+                format!("not {}", name)
+            }
+        };
+        let mut parts = vec![];
+        if let Some(min) = self.min_size {
+            parts.push(format!("size >= {}", format_byte_size(min)));
+        }
+        if let Some(max) = self.max_size {
+            parts.push(format!("size <= {}", format_byte_size(max)));
+        }
+        if let Some(value) = self.world_writable {
+            parts.push(flag("world-writable", value));
+        }
+        if let Some(value) = self.executable {
+            parts.push(flag("executable", value));
+        }
+        if let Some(value) = self.setuid {
+            parts.push(flag("setuid", value));
+        }
+        if let Some(uid) = self.owner {
+            parts.push(format!("uid {}", uid));
+        }
+        if let Some(gid) = self.group {
+            parts.push(format!("gid {}", gid));
+        }
+        for (label, duration) in &[
+            ("modified within", self.modified_within),
+            ("modified older than", self.modified_older_than),
+            ("changed within", self.changed_within),
+            ("changed older than", self.changed_older_than),
+        ] {
+            if let Some(duration) = duration {
+                parts.push(format!("{} {}", label, format_duration(duration.as_secs())));
+            }
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Resolve the user name or id to the user id
+fn resolve_user(name: &str) -> Result<u32> {
+    if let Ok(uid) = name.parse::<u32>() {
+        return Ok(uid);
+    }
+    let c_name = CString::new(name).map_err(|_| format!("Invalid user name '{}'", name))?;
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let status = unsafe {
+        libc::getpwnam_r(
+            c_name.as_ptr(),
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if status != 0 || result.is_null() {
+        bail!("Unknown user '{}'", name);
+    }
+
+    Ok(passwd.pw_uid)
+}
+
+/// Resolve the group name or id to the group id
+fn resolve_group(name: &str) -> Result<u32> {
+    if let Ok(gid) = name.parse::<u32>() {
+        return Ok(gid);
+    }
+    let c_name = CString::new(name).map_err(|_| format!("Invalid group name '{}'", name))?;
+    let mut group: libc::group = unsafe { std::mem::zeroed() };
+    let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+    let mut result: *mut libc::group = std::ptr::null_mut();
+    let status = unsafe {
+        libc::getgrnam_r(
+            c_name.as_ptr(),
+            &mut group,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if status != 0 || result.is_null() {
+        bail!("Unknown group '{}'", name);
+    }
+
+    Ok(group.gr_gid)
+}
+
+const DURATION_UNITS: [(u64, char); 5] = [
+    (7 * 24 * 60 * 60, 'w'),
+    (24 * 60 * 60, 'd'),
+    (60 * 60, 'h'),
+    (60, 'm'),
+    (1, 's'),
+];
+
+/// Parse a number of seconds with an optional unit suffix (e.g. `90`, `30m`, `12h`, `3d` or `2w`)
+pub fn parse_duration(input: &str) -> Result<u64> {
+    let invalid = || -> Error { format!("Invalid duration '{}'", input).into() };
+    let trimmed = input.trim();
+    let (number, factor) = match DURATION_UNITS
+        .iter()
+        .find(|(_, unit)| trimmed.ends_with(*unit))
+    {
+        Some((factor, _)) => (&trimmed[..trimmed.len() - 1], *factor),
+        None => (trimmed, 1),
+    };
+
+    number
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| invalid())?
+        .checked_mul(factor)
+        .ok_or_else(invalid)
+}
+
+/// Format the number of seconds using the largest unit it is a multiple of (e.g. `3d`)
+pub fn format_duration(seconds: u64) -> String {
+    for (factor, unit) in &DURATION_UNITS {
+        if seconds > 0 && seconds.is_multiple_of(*factor) {
+            return format!("{}{}", seconds / factor, unit);
+        }
+    }
+
+    format!("{}s", seconds)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn stat(mode: u32, age_in_days: u64) -> (FileStat, SystemTime) {
+        let now = UNIX_EPOCH + Duration::from_secs(1000 * DAY);
+        let time = now - Duration::from_secs(age_in_days * DAY);
+        let stat = FileStat {
+            size: 2048,
+            mode,
+            uid: 33,
+            gid: 33,
+            modified: time,
+            changed: time,
+        };
+
+        (stat, now)
+    }
+
+    #[test]
+    fn parse_duration_test() {
+        assert_eq!(90, parse_duration("90").unwrap());
+        assert_eq!(90, parse_duration("90s").unwrap());
+        assert_eq!(30 * 60, parse_duration("30m").unwrap());
+        assert_eq!(12 * 60 * 60, parse_duration("12h").unwrap());
+        assert_eq!(3 * DAY, parse_duration(" 3d ").unwrap());
+        assert_eq!(14 * DAY, parse_duration("2w").unwrap());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("-1d").is_err());
+        assert!(parse_duration("1y").is_err());
+    }
+
+    #[test]
+    fn format_duration_test() {
+        assert_eq!("0s", format_duration(0));
+        assert_eq!("90s", format_duration(90));
+        assert_eq!("3d", format_duration(3 * DAY));
+        assert_eq!("2w", format_duration(14 * DAY));
+    }
+
+    #[test]
+    fn matches_test() {
+        let condition = FileCondition {
+            world_writable: Some(true),
+            modified_within: Some(Duration::from_secs(3 * DAY)),
+            ..FileCondition::default()
+        };
+
+        let (recent_writable, now) = stat(0o666, 1);
+        assert!(condition.matches(&recent_writable, now));
+        let (old_writable, now) = stat(0o666, 4);
+        assert!(!condition.matches(&old_writable, now));
+        let (recent_protected, now) = stat(0o644, 1);
+        assert!(!condition.matches(&recent_protected, now));
+        assert!(FileCondition::default().matches(&recent_protected, now));
+    }
+
+    #[test]
+    fn matches_size_owner_and_mode_test() {
+        let (stat, now) = stat(0o4755, 10);
+        let matches = |condition: FileCondition| condition.matches(&stat, now);
+
+        assert!(matches(FileCondition {
+            min_size: Some(1024),
+            max_size: Some(2048),
+            ..FileCondition::default()
+        }));
+        assert!(!matches(FileCondition {
+            min_size: Some(4096),
+            ..FileCondition::default()
+        }));
+        assert!(matches(FileCondition {
+            setuid: Some(true),
+            executable: Some(true),
+            world_writable: Some(false),
+            ..FileCondition::default()
+        }));
+        assert!(matches(FileCondition {
+            owner: Some(33),
+            group: Some(33),
+            changed_older_than: Some(Duration::from_secs(7 * DAY)),
+            ..FileCondition::default()
+        }));
+        assert!(!matches(FileCondition {
+            owner: Some(0),
+            ..FileCondition::default()
+        }));
+    }
+
+    #[test]
+    fn try_from_test() {
+        let raw = RawFileCondition {
+            owner: Some("root".to_owned()),
+            group: Some("33".to_owned()),
+            modified_within: Some(60),
+            ..RawFileCondition::default()
+        };
+        let condition = FileCondition::try_from(&raw).unwrap();
+        assert_eq!(Some(0), condition.owner);
+        assert_eq!(Some(33), condition.group);
+        assert_eq!(Some(Duration::from_secs(60)), condition.modified_within);
+        assert_eq!("uid 0, gid 33, modified within 1m", condition.to_string());
+
+        let unknown_user = RawFileCondition {
+            owner: Some("no-such-user-hackscanner".to_owned()),
+            ..RawFileCondition::default()
+        };
+        assert!(FileCondition::try_from(&unknown_user).is_err());
+        let invalid_size = RawFileCondition {
+            min_size: Some(2048),
+            max_size: Some(1024),
+            ..RawFileCondition::default()
+        };
+        assert!(FileCondition::try_from(&invalid_size).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::errors::*;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Algorithm used to compute the hash of a file's content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// Detect the algorithm from the number of hex digits of a hash
+    fn from_hex_length(length: usize) -> Option<Self> {
+        match length {
+            32 => Some(HashAlgorithm::Md5),
+            40 => Some(HashAlgorithm::Sha1),
+            64 => Some(HashAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashAlgorithm::Md5 => write!(f, "MD5"),
+            HashAlgorithm::Sha1 => write!(f, "SHA-1"),
+            HashAlgorithm::Sha256 => write!(f, "SHA-256"),
+        }
+    }
+}
+
+/// Set of MD5, SHA-1 and SHA-256 hashes of file contents
+///
+/// The algorithm of each hash is detected from its length. Hashes are stored as lowercase hex
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HashList {
+    hashes: BTreeMap<HashAlgorithm, HashSet<String>>,
+}
+
+impl HashList {
+    /// Build a list from the given hex encoded hashes
+    pub fn from_hashes<S: AsRef<str>>(hashes: &[S]) -> Result<Self> {
+        let mut list = HashList::default();
+        for hash in hashes {
+            list.insert(hash.as_ref())?;
+        }
+
+        Ok(list)
+    }
+
+    /// Add the hex encoded `hash`
+    pub fn insert(&mut self, hash: &str) -> Result<()> {
+        let hash = hash.trim().to_lowercase();
+        let algorithm = match HashAlgorithm::from_hex_length(hash.len()) {
+            Some(algorithm) if hash.chars().all(|c| c.is_ascii_hexdigit()) => algorithm,
+            _ => bail!(
+                "Invalid hash '{}': expected 32 (MD5), 40 (SHA-1) or 64 (SHA-256) hex digits",
+                hash
+            ),
+        };
+        self.hashes.entry(algorithm).or_default().insert(hash);
+
+        Ok(())
+    }
+
+    /// Add the hashes from the hash list file at `path`
+    ///
+    /// Each line contains one hash. Text following the hash is ignored, so that the output of
+    /// `sha256sum` and similar tools can be used. Empty lines and lines starting with `#` are
+    /// skipped
+    pub fn read_file(&mut self, path: &Path) -> Result<()> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => bail!("Could not read hash list file '{}': {}", path.display(), e),
+        };
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let hash = line.split_whitespace().next().unwrap_or_default();
+            if let Err(e) = self.insert(hash) {
+                bail!("{} in line {} of file '{}'", e, index + 1, path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the algorithms of the contained hashes
+    pub fn algorithms(&self) -> impl Iterator<Item = HashAlgorithm> + '_ {
+        self.hashes.keys().copied()
+    }
+
+    /// Return if the list contains the lowercase hex encoded `hash` computed with `algorithm`
+    pub fn contains(&self, algorithm: HashAlgorithm, hash: &str) -> bool {
+        self.hashes
+            .get(&algorithm)
+            .is_some_and(|hashes| hashes.contains(hash))
+    }
+
+    /// Return the number of hashes
+    pub fn len(&self) -> usize {
+        self.hashes.values().map(HashSet::len).sum()
+    }
+
+    /// Return if the list does not contain any hash
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_helpers::TempDir;
+
+    const SHA256: &str = "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+
+    #[test]
+    fn from_hashes_test() {
+        let list = HashList::from_hashes(&[
+            "d41d8cd98f00b204e9800998ecf8427e",
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            SHA256,
+        ])
+        .unwrap();
+
+        assert_eq!(3, list.len());
+        assert_eq!(
+            vec![
+                HashAlgorithm::Md5,
+                HashAlgorithm::Sha1,
+                HashAlgorithm::Sha256
+            ],
+            list.algorithms().collect::<Vec<_>>()
+        );
+        assert!(list.contains(HashAlgorithm::Sha256, &SHA256.to_lowercase()));
+        assert!(!list.contains(HashAlgorithm::Md5, &SHA256.to_lowercase()));
+    }
+
+    #[test]
+    fn insert_invalid_test() {
+        let mut list = HashList::default();
+        assert!(list.insert("d41d8cd98f00b204e9800998ecf8427").is_err());
+        assert!(list.insert("x41d8cd98f00b204e9800998ecf8427e").is_err());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn read_file_test() {
+        let dir = TempDir::new("hash-list-test");
+        let path = dir.file(
+            "hashes.txt",
+            format!(
+                "# Known web shells\n\n{}  shell.php\nd41d8cd98f00b204e9800998ecf8427e\n",
+                SHA256
+            ),
+        );
+        let mut list = HashList::default();
+        let result = list.read_file(&path);
+        fs::write(&path, "no-hash\n").unwrap();
+        let invalid_result = HashList::default().read_file(&path);
+
+        assert!(result.is_ok(), "{}", result.unwrap_err());
+        assert_eq!(2, list.len());
+        let error = invalid_result.unwrap_err().to_string();
+        assert!(error.contains("Invalid hash 'no-hash'"), "{}", error);
+        assert!(error.contains("in line 1 of file"), "{}", error);
+    }
+}
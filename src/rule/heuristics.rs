@@ -0,0 +1,122 @@
+use super::raw_read_limit::deserialize_byte_size;
+use super::read_limit::format_byte_size;
+use crate::errors::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Thresholds of the content metrics that indicate obfuscated code
+///
+/// A file violates the Rule if each defined metric of its content reaches the threshold. Sizes
+/// may be given as number of bytes or as string with a unit (e.g. `"4K"`)
+#[derive(Debug, Clone, Copy, Default, PartialOrd, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Heuristics {
+    /// Minimum Shannon entropy in bits per byte (0 to 8)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_entropy: Option<f64>,
+
+    /// Minimum length of the longest line in bytes
+    #[serde(
+        default,
+        deserialize_with = "deserialize_byte_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_longest_line: Option<u64>,
+
+    /// Minimum ratio of non-alphanumeric characters (0 to 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_non_alphanumeric_ratio: Option<f64>,
+
+    /// Minimum size of the largest base64 or hex encoded blob in bytes
+    #[serde(
+        default,
+        deserialize_with = "deserialize_byte_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_blob_size: Option<u64>,
+}
+
+impl Heuristics {
+    /// Return if no threshold is defined
+    pub fn is_empty(&self) -> bool {
+        self == &Heuristics::default()
+    }
+
+    /// Return an error if a threshold is out of range
+    pub fn validate(&self) -> Result<()> {
+        if let Some(entropy) = self.min_entropy {
+            if !(0.0..=8.0).contains(&entropy) {
+                bail!("The minimum entropy ({}) must be between 0 and 8", entropy);
+            }
+        }
+        if let Some(ratio) = self.min_non_alphanumeric_ratio {
+            if !(0.0..=1.0).contains(&ratio) {
+                bail!(
+                    "The minimum non-alphanumeric ratio ({}) must be between 0 and 1",
+                    ratio
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Heuristics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        if let Some(entropy) = self.min_entropy {
+            parts.push(format!("entropy >= {}", entropy));
+        }
+        if let Some(length) = self.min_longest_line {
+            parts.push(format!("longest line >= {}", format_byte_size(length)));
+        }
+        if let Some(ratio) = self.min_non_alphanumeric_ratio {
+            parts.push(format!("non-alphanumeric >= {:.0}%", ratio * 100.0));
+        }
+        if let Some(size) = self.min_blob_size {
+            parts.push(format!("blob >= {}", format_byte_size(size)));
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate_test() {
+        assert!(Heuristics::default().validate().is_ok());
+        let valid = Heuristics {
+            min_entropy: Some(5.5),
+            min_non_alphanumeric_ratio: Some(0.4),
+            ..Heuristics::default()
+        };
+        assert!(valid.validate().is_ok());
+        assert_eq!("entropy >= 5.5, non-alphanumeric >= 40%", valid.to_string());
+
+        let entropy = Heuristics {
+            min_entropy: Some(9.0),
+            ..Heuristics::default()
+        };
+        assert!(entropy.validate().is_err());
+        let ratio = Heuristics {
+            min_non_alphanumeric_ratio: Some(40.0),
+            ..Heuristics::default()
+        };
+        assert!(ratio.validate().is_err());
+    }
+
+    #[test]
+    fn deserialize_test() {
+        let heuristics: Heuristics =
+            serde_yaml::from_str("min_entropy: 5.8\nmin_blob_size: 4K\n").unwrap();
+        assert_eq!(Some(5.8), heuristics.min_entropy);
+        assert_eq!(Some(4096), heuristics.min_blob_size);
+        assert_eq!(None, heuristics.min_longest_line);
+
+        assert!(serde_yaml::from_str::<Heuristics>("entropy: 5.8\n").is_err());
+    }
+}
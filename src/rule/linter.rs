@@ -0,0 +1,454 @@
+//! Static checks of rule files
+use crate::errors::{Error, ErrorKind};
+use crate::matcher::Matcher;
+use crate::rule::builtin::get_builtin_rules;
+use crate::rule::pattern_rule::PatternRule;
+use crate::rule::raw_rule::RawRule;
+use crate::rule::reader::{Reader, ReaderIssue};
+use crate::rule::{RawPathKind, RuleTrait};
+use crate::Severity;
+use regex::bytes::Regex as BytesRegex;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Level of a problem found by [`check_rules`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LintLevel {
+    /// The rule or file can not be loaded
+    Error,
+    /// The rule can be loaded, but is most likely not doing what it was written for
+    Warning,
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintLevel::Error => write!(f, "error"),
+            LintLevel::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Problem found by [`check_rules`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    pub level: LintLevel,
+    /// File the problem was found in
+    pub file: Option<PathBuf>,
+    /// Name of the affected rule
+    pub rule: Option<String>,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn new<S: Into<String>>(
+        level: LintLevel,
+        file: Option<&Path>,
+        rule: Option<&str>,
+        message: S,
+    ) -> Self {
+        Self {
+            level,
+            file: file.map(Path::to_owned),
+            rule: rule.map(str::to_owned),
+            message: message.into(),
+        }
+    }
+
+    fn for_rule<S: Into<String>>(level: LintLevel, rule: &PatternRule, message: S) -> Self {
+        Self::new(level, rule.source(), Some(rule.name()), message)
+    }
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.level)?;
+        if let Some(ref file) = self.file {
+            write!(f, " in '{}'", file.display())?;
+        }
+        if let Some(ref rule) = self.rule {
+            write!(f, " rule '{}'", rule)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Check the rules of the given files and directories
+///
+/// Reports invalid files and rules, duplicate rule names, copies of builtin rules and rules that
+/// can never create a violation, because the file finder never lets a matching file through
+pub fn check_rules<P: AsRef<Path>>(paths: &[P], with_builtin: bool) -> Vec<LintIssue> {
+    check_rules_with_builtin(paths, with_builtin.then(get_builtin_rules))
+}
+
+/// Check the rules of the given files and directories against the given builtin rules
+///
+/// See [`check_rules`] for details
+pub fn check_rules_with_builtin<P: AsRef<Path>>(
+    paths: &[P],
+    builtin_rules: Option<Vec<PatternRule>>,
+) -> Vec<LintIssue> {
+    let (configuration, reader_issues) = Reader::read_configuration_leniently(paths);
+    let builtin_rules = match builtin_rules {
+        Some(builtin_rules) => configuration.builtin_changes.apply(builtin_rules),
+        None => vec![],
+    };
+    let custom_rules = &configuration.rules;
+    let all_rules: Vec<&PatternRule> = custom_rules.iter().chain(builtin_rules.iter()).collect();
+
+    let mut issues: Vec<LintIssue> = reader_issues.iter().flat_map(check_reader_issue).collect();
+    issues.extend(check_duplicate_names(custom_rules, &builtin_rules));
+    issues.extend(check_builtin_copies(custom_rules, &builtin_rules));
+    issues.extend(
+        custom_rules
+            .iter()
+            .filter_map(|rule| check_reachability(rule, &all_rules)),
+    );
+
+    issues
+}
+
+fn check_reader_issue(issue: &ReaderIssue) -> Vec<LintIssue> {
+    let regex_issues: Vec<LintIssue> = match issue.raw_rule {
+        Some(ref raw_rule) => check_regexes(raw_rule)
+            .into_iter()
+            .map(|message| {
+                LintIssue::new(
+                    LintLevel::Error,
+                    Some(&issue.path),
+                    Some(raw_rule.name()),
+                    message,
+                )
+            })
+            .collect(),
+        None => vec![],
+    };
+    if !regex_issues.is_empty() {
+        return regex_issues;
+    }
+
+    vec![LintIssue::new(
+        LintLevel::Error,
+        Some(&issue.path),
+        issue.raw_rule.as_ref().map(RawRule::name),
+        issue.error.to_string(),
+    )]
+}
+
+/// Compile the regular expressions of the `raw_rule` and describe the errors
+///
+/// Content patterns are compiled as byte regular expressions like in `PatternRule`
+fn check_regexes(raw_rule: &RawRule) -> Vec<String> {
+    let mut messages = vec![];
+    if raw_rule.is_regex_path() {
+        let pattern = raw_rule.path_ref();
+        if let Some(e) = describe_regex_error(pattern, false) {
+            messages.push(format!("Invalid path pattern '{}': {}", pattern, e));
+        }
+    }
+    if let Some(content) = raw_rule.content() {
+        for pattern in content.patterns() {
+            if let Some(e) = describe_regex_error(pattern, true) {
+                messages.push(format!("Invalid content pattern '{}': {}", pattern, e));
+            }
+        }
+    }
+    if let Some(content_hex) = raw_rule.content_hex() {
+        for hex in content_hex.patterns() {
+            match PatternRule::build_hex_pattern(raw_rule.name(), hex) {
+                Ok(pattern) => {
+                    if let Some(e) = describe_regex_error(&pattern, true) {
+                        messages.push(format!("Invalid content_hex pattern '{}': {}", hex, e));
+                    }
+                }
+                Err(Error(ErrorKind::RuleError(message), _)) => messages.push(message),
+                Err(e) => messages.push(e.to_string()),
+            }
+        }
+    }
+
+    messages
+}
+
+/// Return the position and reason if the `pattern` is not a valid regular expression
+///
+/// If `bytes` is set, the pattern is checked as `regex::bytes::Regex`, which may match invalid
+/// UTF-8
+fn describe_regex_error(pattern: &str, bytes: bool) -> Option<String> {
+    let parsed = regex_syntax::ParserBuilder::new()
+        .utf8(!bytes)
+        .build()
+        .parse(pattern);
+    let (span, reason) = match parsed {
+        Ok(_) if bytes => return BytesRegex::new(pattern).err().map(|e| e.to_string()),
+        Ok(_) => return Regex::new(pattern).err().map(|e| e.to_string()),
+        Err(regex_syntax::Error::Parse(e)) => (*e.span(), e.kind().to_string()),
+        Err(regex_syntax::Error::Translate(e)) => (*e.span(), e.kind().to_string()),
+        Err(e) => return Some(e.to_string()),
+    };
+
+    Some(format!("{} at column {}", reason, span.start.column))
+}
+
+fn check_duplicate_names(
+    custom_rules: &[PatternRule],
+    builtin_rules: &[PatternRule],
+) -> Vec<LintIssue> {
+    let mut issues = vec![];
+    let mut seen: HashMap<&str, &PatternRule> = HashMap::new();
+    for rule in custom_rules {
+        match seen.get(rule.name()) {
+            Some(first) => issues.push(LintIssue::for_rule(
+                LintLevel::Warning,
+                rule,
+                format!(
+                    "Duplicate rule name (first defined in '{}')",
+                    describe_source(first)
+                ),
+            )),
+            None => {
+                seen.insert(rule.name(), rule);
+            }
+        }
+        if builtin_rules.iter().any(|b| b.name() == rule.name()) {
+            issues.push(LintIssue::for_rule(
+                LintLevel::Warning,
+                rule,
+                "Rule name is also used by a builtin rule",
+            ));
+        }
+    }
+
+    issues
+}
+
+fn check_builtin_copies(
+    custom_rules: &[PatternRule],
+    builtin_rules: &[PatternRule],
+) -> Vec<LintIssue> {
+    custom_rules
+        .iter()
+        .filter_map(|rule| {
+            builtin_rules
+                .iter()
+                .find(|builtin| {
+                    builtin.path_kind() == rule.path_kind()
+                        && builtin.match_on() == rule.match_on()
+                        && builtin.extensions() == rule.extensions()
+                        && builtin.any_extension() == rule.any_extension()
+                        && builtin.raw_path() == rule.raw_path()
+                        && builtin.is_case_sensitive_path() == rule.is_case_sensitive_path()
+                        && builtin.raw_content() == rule.raw_content()
+                        && builtin.raw_content_hex() == rule.raw_content_hex()
+                        && builtin.severity() == rule.severity()
+                        && builtin.flags() == rule.flags()
+                        && builtin.raw_read_limit() == rule.raw_read_limit()
+                        && builtin.raw_hashes() == rule.raw_hashes()
+                        && builtin.hash_files() == rule.hash_files()
+                        && builtin.raw_file_condition() == rule.raw_file_condition()
+                        && builtin.heuristics() == rule.heuristics()
+                })
+                .map(|builtin| {
+                    LintIssue::for_rule(
+                        LintLevel::Warning,
+                        rule,
+                        format!("Rule is identical to builtin rule '{}'", builtin.name()),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Check if the file finder lets any file through that the `rule`'s path matches
+fn check_reachability(rule: &PatternRule, all_rules: &[&PatternRule]) -> Option<LintIssue> {
+    if rule.severity() == Severity::WHITELIST {
+        return None;
+    }
+    let subject = if rule.has_content() {
+        "Content rule is never applied"
+    } else {
+        "Rule can never match"
+    };
+    if matches_only_directories(rule) {
+        return Some(LintIssue::for_rule(
+            LintLevel::Warning,
+            rule,
+            format!(
+                "{}: path '{}' only matches directories, but only files are scanned",
+                subject,
+                rule.raw_path()
+            ),
+        ));
+    }
+
+    all_rules
+        .iter()
+        .filter(|other| other.severity() == Severity::WHITELIST)
+        .find(|whitelist| excludes_all_paths_of(whitelist, rule))
+        .map(|whitelist| {
+            LintIssue::for_rule(
+                LintLevel::Warning,
+                rule,
+                format!(
+                    "{}: every file matching path '{}' is excluded by WHITELIST rule '{}' ('{}')",
+                    subject,
+                    rule.raw_path(),
+                    whitelist.name(),
+                    whitelist.raw_path()
+                ),
+            )
+        })
+}
+
+/// Return if paths matching the `rule` must end with a slash (which file paths never do)
+fn matches_only_directories(rule: &PatternRule) -> bool {
+    let path = rule.raw_path();
+    match rule.path_kind() {
+        RawPathKind::String => false,
+        RawPathKind::Regex => path.ends_with("/$") || path.ends_with(r"/\z"),
+        RawPathKind::Glob => path.ends_with('/'),
+    }
+}
+
+/// Return if every path matching the `rule` is also matched by the `whitelist` rule
+///
+/// Only detects the obvious cases: identical paths and string paths containing a string (or an
+/// unanchored regular expression) of the `whitelist` rule. A case-sensitive `whitelist` path
+/// does not exclude the other casings matched by a case-insensitive `rule` path. A `whitelist`
+/// rule with a hash list or a file condition only excludes some of the files
+fn excludes_all_paths_of(whitelist: &PatternRule, rule: &PatternRule) -> bool {
+    if whitelist.match_on() != rule.match_on() || !excludes_all_extensions_of(whitelist, rule) {
+        return false;
+    }
+    if whitelist.has_hashes() || whitelist.file_condition().is_some() {
+        return false;
+    }
+    if whitelist.is_case_sensitive_path() && !rule.is_case_sensitive_path() {
+        return false;
+    }
+    if whitelist.path_kind() == rule.path_kind() && whitelist.raw_path() == rule.raw_path() {
+        return true;
+    }
+    if rule.path_kind() != RawPathKind::String {
+        return false;
+    }
+
+    match whitelist.path_kind() {
+        RawPathKind::String => Matcher::match_path_str(whitelist, rule.raw_path()),
+        RawPathKind::Regex => {
+            !is_anchored(whitelist.raw_path())
+                && Matcher::match_path_str(whitelist, rule.raw_path())
+        }
+        RawPathKind::Glob => false,
+    }
+}
+
+/// Return if every extension accepted by the `rule` is also accepted by the `whitelist` rule
+fn excludes_all_extensions_of(whitelist: &PatternRule, rule: &PatternRule) -> bool {
+    if whitelist.extensions().is_empty() {
+        return true;
+    }
+
+    !rule.extensions().is_empty()
+        && (whitelist.any_extension() || !rule.any_extension())
+        && rule
+            .extensions()
+            .iter()
+            .all(|extension| whitelist.extensions().contains(extension))
+}
+
+/// Return if the regular expression depends on the position of the match inside the path
+fn is_anchored(pattern: &str) -> bool {
+    pattern.contains('^')
+        || pattern.contains('$')
+        || pattern.contains(r"\A")
+        || pattern.contains(r"\z")
+        || pattern.contains(r"\b")
+        || pattern.contains(r"\B")
+}
+
+fn describe_source(rule: &PatternRule) -> String {
+    rule.source()
+        .map_or_else(|| "builtin".to_owned(), |s| s.display().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check(file: &str, with_builtin: bool) -> Vec<LintIssue> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/resources/rules/check")
+            .join(file);
+
+        check_rules(&[path], with_builtin)
+    }
+
+    fn messages(issues: &[LintIssue], level: LintLevel) -> Vec<String> {
+        issues
+            .iter()
+            .filter(|i| i.level == level)
+            .map(|i| format!("{}: {}", i.rule.as_deref().unwrap_or("-"), i.message))
+            .collect()
+    }
+
+    #[test]
+    fn valid_rules_test() {
+        assert_eq!(Vec::<LintIssue>::new(), check("valid.yaml", true));
+    }
+
+    #[test]
+    fn invalid_regex_test() {
+        let issues = check("invalid_regex.yaml", false);
+        assert_eq!(
+            vec![
+                "broken path: Invalid path pattern 'wp-(admin': unclosed group at column 4"
+                    .to_owned(),
+                "broken content: Invalid content pattern 'eval\\(+[': unclosed character class at column 8"
+                    .to_owned(),
+                "broken bytes content: Invalid content pattern 'eval(': unclosed group at column 5"
+                    .to_owned(),
+                "broken bytes content: Invalid byte '5g' in hex pattern '4d 5g' of rule 'broken bytes content'"
+                    .to_owned(),
+            ],
+            messages(&issues, LintLevel::Error)
+        );
+        // The byte pattern `(?-u:\xff\xfe)` is valid for content
+        // The valid rule after the broken ones is still checked
+        let warnings = messages(&issues, LintLevel::Warning);
+        assert_eq!(1, warnings.len());
+        assert!(warnings[0].starts_with("duplicate: Duplicate rule name (first defined in '"));
+        assert!(warnings[0].ends_with("invalid_regex.yaml')"));
+    }
+
+    #[test]
+    fn builtin_copies_test() {
+        let issues = check("builtin_copy.yaml", true);
+        assert_eq!(
+            vec![
+                "php::content::eval: Rule name is also used by a builtin rule".to_owned(),
+                "php::content::eval: Rule is identical to builtin rule 'php::content::eval'"
+                    .to_owned(),
+                "my eval: Rule is identical to builtin rule 'php::content::eval'".to_owned(),
+            ],
+            messages(&issues, LintLevel::Warning)
+        );
+        assert_eq!(Vec::<LintIssue>::new(), check("builtin_copy.yaml", false));
+    }
+
+    #[test]
+    fn unreachable_rules_test() {
+        // The WHITELIST rules with hashes or a file condition only exclude some of the files
+        let issues = check("unreachable.yaml", false);
+        assert_eq!(
+            vec![
+                "cache eval: Content rule is never applied: every file matching path 'var/cache/' is excluded by WHITELIST rule 'cache' ('/cache/')".to_owned(),
+                "cache php: Rule can never match: every file matching path 'cache/file.php' is excluded by WHITELIST rule 'tmp' ('[a-z]+/file')".to_owned(),
+                "uploads dir: Content rule is never applied: path 'uploads/' only matches directories, but only files are scanned".to_owned(),
+            ],
+            messages(&issues, LintLevel::Warning)
+        );
+    }
+}
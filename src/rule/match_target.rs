@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Part of an entry's path a Rule's path pattern is matched against
+#[derive(Debug, Clone, Copy, Default, PartialOrd, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchTarget {
+    /// The file name (e.g. `shell.php`)
+    FileName,
+    /// The directory containing the file (e.g. `/var/www/uploads`)
+    Dir,
+    /// The full path as found while walking the root (e.g. `/var/www/uploads/shell.php`)
+    #[default]
+    Path,
+    /// The path relative to the scanned root (e.g. `uploads/shell.php`)
+    RelativePath,
+}
+
+impl MatchTarget {
+    /// Return if the target is the default target `path`
+    pub fn is_default(&self) -> bool {
+        *self == MatchTarget::default()
+    }
+}
+
+impl fmt::Display for MatchTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchTarget::FileName => write!(f, "file_name"),
+            MatchTarget::Dir => write!(f, "dir"),
+            MatchTarget::Path => write!(f, "path"),
+            MatchTarget::RelativePath => write!(f, "relative_path"),
+        }
+    }
+}
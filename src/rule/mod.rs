@@ -10,34 +10,119 @@
 //! If only `path` is defined, a matching file path violates the [`Rule`].
 //!
 //! If only `content` is defined, a matching file content violates the [`Rule`].
+//!
+//! A [`Rule`] may define `heuristics` thresholds of content metrics (e.g. the entropy) that
+//! indicate obfuscated code.
+//!
+//! A [`Rule`] may also define `file` conditions on the size, permissions, owner and timestamps of
+//! the file, which have to be fulfilled in addition to the `path` and `content` conditions.
+//!
+//! The `content` is either a single pattern or a [`ContentGroup`] with `all`, `any` and `none`
+//! lists of patterns, which may be nested.
 mod builtin;
+mod configuration;
+mod content_pattern;
+mod file_condition;
+mod hash_list;
+mod heuristics;
+mod linter;
+mod match_target;
 mod pattern_rule;
+mod raw_content;
+mod raw_file_condition;
 mod raw_path;
+mod raw_read_limit;
 mod raw_rule;
+mod read_limit;
 mod reader;
+mod regex_flags;
+mod rule_examples;
+mod rule_metadata;
+mod rule_pack;
 mod rule_path;
 mod rule_trait;
+mod writer;
 
-pub use self::builtin::get_builtin_rules;
+pub use self::builtin::{
+    get_builtin_rule_packs, get_builtin_rules, get_builtin_rules_from_packs,
+    get_embedded_rule_packs,
+};
+pub use self::configuration::{matches_name, BuiltinChanges, Configuration, SeverityOverride};
+pub use self::content_pattern::{ContentGroup, ContentPattern};
+pub use self::file_condition::{format_duration, parse_duration, FileCondition, FileStat};
+pub use self::hash_list::{HashAlgorithm, HashList};
+pub use self::heuristics::Heuristics;
+pub use self::linter::{check_rules, check_rules_with_builtin, LintIssue, LintLevel};
+pub use self::match_target::MatchTarget;
 pub use self::pattern_rule::PatternRule as Rule;
-pub use self::raw_path::RawPath;
+pub use self::raw_content::{RawContent, RawContentGroup};
+pub use self::raw_file_condition::RawFileCondition;
+pub use self::raw_path::{RawPath, RawPathKind};
+pub use self::raw_read_limit::RawReadLimit;
+pub use self::read_limit::{format_byte_size, parse_byte_size, ReadLimit};
+pub use self::regex_flags::RegexFlags;
+pub use self::rule_examples::{RuleExample, RuleExamples};
+pub use self::rule_metadata::RuleMetadata;
+pub use self::rule_pack::RulePack;
 pub use self::rule_path::RulePath;
 use crate::errors::*;
 pub use rule_trait::RuleTrait;
 use std::path::Path;
+pub use writer::{export_rules, ExportFormat};
 
 /// Read the `Rule`s from the given path and merge them with the builtin rules
 pub fn get_merged_rules<P: AsRef<Path>>(path: P) -> Result<Vec<Rule>, Error> {
-    let path = path.as_ref();
+    get_merged_rules_from_paths(&[path])
+}
+
+/// Read the `Rule`s from the given files and directories and merge them with the builtin rules
+///
+/// Directories are searched for rule files, which are read in alphabetical order. Rule files may
+/// load other files or directories with the `include` directive.
+pub fn get_merged_rules_from_paths<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<Rule>, Error> {
+    get_rules_from_paths(paths, true)
+}
+
+/// Read the `Rule`s from the given files and directories
+///
+/// If `with_builtin` is `true` the builtin rules are appended, after the `disable` and `override`
+/// directives of the configuration files have been applied to them.
+pub fn get_rules_from_paths<P: AsRef<Path>>(
+    paths: &[P],
+    with_builtin: bool,
+) -> Result<Vec<Rule>, Error> {
+    get_rules_from_paths_with_builtin(paths, with_builtin.then(get_builtin_rules))
+}
 
-    let mut collection = reader::Reader::read_rules_from_file(path)?;
+/// Read the `Rule`s from the given files and directories and append the given builtin rules
+///
+/// The `disable` and `override` directives of the configuration files are applied to the
+/// `builtin_rules` (e.g. the rules returned by [`get_builtin_rules_from_packs`])
+pub fn get_rules_from_paths_with_builtin<P: AsRef<Path>>(
+    paths: &[P],
+    builtin_rules: Option<Vec<Rule>>,
+) -> Result<Vec<Rule>, Error> {
+    let configuration = reader::Reader::read_configuration_from_paths(paths)?;
+    let mut collection = configuration.rules;
     info!(
-        "Read {} custom rule(s) from '{}'",
+        "Read {} custom rule(s) from {}",
         collection.len(),
-        path.display()
+        paths
+            .iter()
+            .map(|p| format!("'{}'", p.as_ref().display()))
+            .collect::<Vec<String>>()
+            .join(", ")
     );
     trace!("Custom rules: {:?}", collection);
-    collection.append(&mut get_builtin_rules());
+    match builtin_rules {
+        Some(builtin_rules) => {
+            collection.append(&mut configuration.builtin_changes.apply(builtin_rules))
+        }
+        None if !configuration.builtin_changes.is_empty() => {
+            info!("Ignore the changes of the builtin rules, because they are disabled")
+        }
+        None => {}
+    }
 
     Ok(collection)
 }
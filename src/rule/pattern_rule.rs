@@ -1,12 +1,28 @@
 use self::regex_cache::RegexCache;
+use super::content_pattern::{ContentGroup, ContentPattern};
+use super::file_condition::FileCondition;
+use super::hash_list::HashList;
+use super::heuristics::Heuristics;
+use super::match_target::MatchTarget;
+use super::raw_content::{RawContent, RawContentGroup};
+use super::raw_file_condition::RawFileCondition;
+use super::raw_path::RawPathKind;
+use super::raw_read_limit::RawReadLimit;
 use super::raw_rule::RawPath;
 use super::raw_rule::RawRule;
+use super::read_limit::ReadLimit;
 use super::RuleTrait;
 use crate::errors::*;
+use crate::rule::regex_flags::RegexFlags;
+use crate::rule::rule_examples::RuleExamples;
+use crate::rule::rule_metadata::RuleMetadata;
 use crate::rule::rule_path::RulePath;
 use crate::severity::Severity;
+use globset::{GlobBuilder, GlobMatcher};
 use regex::Regex;
 use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 mod regex_cache;
 
@@ -17,8 +33,28 @@ pub struct PatternRule {
     path: String,
     is_regex_path: bool,
     path_regex: Option<Regex>,
-    content: Option<Regex>,
+    path_glob: Option<GlobMatcher>,
+    case_sensitive_path: bool,
+    match_on: MatchTarget,
+    extensions: Vec<String>,
+    any_extension: bool,
+    content: Option<ContentPattern>,
+    raw_content: Option<RawContent>,
+    raw_content_hex: Option<RawContent>,
+    raw_hashes: Vec<String>,
+    hash_files: Vec<PathBuf>,
+    /// Shared, because Rules are cloned into every Violation
+    hashes: Option<Arc<HashList>>,
+    heuristics: Heuristics,
+    raw_file_condition: RawFileCondition,
+    file_condition: Option<FileCondition>,
+    flags: RegexFlags,
+    read_limit: RawReadLimit,
+    default_read_limit: ReadLimit,
     severity: Severity,
+    metadata: RuleMetadata,
+    examples: RuleExamples,
+    source: Option<PathBuf>,
 }
 
 impl PartialEq for PatternRule {
@@ -29,16 +65,51 @@ impl PartialEq for PatternRule {
         if self.path != other.path {
             return false;
         }
-        if self.is_regex_path != other.is_regex_path {
+        if self.is_regex_path != other.is_regex_path
+            || self.case_sensitive_path != other.case_sensitive_path
+        {
+            return false;
+        }
+        if self.match_on != other.match_on {
+            return false;
+        }
+        if self.extensions != other.extensions || self.any_extension != other.any_extension {
             return false;
         }
         if self.severity != other.severity {
             return false;
         }
+        if self.metadata != other.metadata {
+            return false;
+        }
+        if self.examples != other.examples {
+            return false;
+        }
+        if self.flags != other.flags {
+            return false;
+        }
+        if self.read_limit() != other.read_limit() {
+            return false;
+        }
         if self.path_regex.is_some() != other.path_regex.is_some() {
             return false;
         }
-        if self.content.is_some() != other.content.is_some() {
+        if self.path_glob.is_some() != other.path_glob.is_some() {
+            return false;
+        }
+        if self.content != other.content {
+            return false;
+        }
+        if self.raw_content_hex != other.raw_content_hex {
+            return false;
+        }
+        if self.raw_hashes != other.raw_hashes || self.hash_files != other.hash_files {
+            return false;
+        }
+        if self.heuristics != other.heuristics {
+            return false;
+        }
+        if self.raw_file_condition != other.raw_file_condition {
             return false;
         }
         if let Some(ref p) = self.path_regex {
@@ -46,50 +117,28 @@ impl PartialEq for PatternRule {
                 return false;
             }
         }
-        if let Some(ref p) = self.content {
-            if p.as_str() != other.content.as_ref().unwrap().as_str() {
-                return false;
-            }
-        }
 
         true
     }
 }
 
 impl PatternRule {
-    pub(crate) fn from_raw_rules(raw: Vec<RawRule>) -> Result<Vec<PatternRule>> {
-        let mut container: Vec<PatternRule> = Vec::with_capacity(raw.len());
-        trace!("Will transform rules to PatternRules");
-        for r in raw {
-            container.push(PatternRule::try_from(r)?);
-        }
-        trace!("Did transform rules to PatternRules");
-        Ok(container)
-    }
-
     pub fn new<S: Into<String>>(
         name: S,
         severity: Severity,
         raw_path: RawPath,
         content: Option<String>,
     ) -> Result<Self> {
-        let is_regex_path = raw_path.is_regex();
-        let path_regex = if is_regex_path {
-            Some(Self::build_regex(raw_path.as_str())?)
-        } else {
-            None
-        };
-        let content = match content {
-            Some(c) => Some(Self::build_regex(&c)?),
+        let name = name.into();
+        let raw_content = content.map(RawContent::Pattern);
+        let content = match &raw_content {
+            Some(c) => Some(Self::build_content(&name, c, RegexFlags::default())?),
             None => None,
         };
-        Ok(Self {
-            name: name.into(),
-            path: raw_path.as_str().to_owned(),
-            is_regex_path,
-            path_regex,
+        Ok(PatternRule {
             content,
-            severity,
+            raw_content,
+            ..Self::with_name_and_path(name, severity, &raw_path)?
         })
     }
 
@@ -98,77 +147,602 @@ impl PatternRule {
         severity: Severity,
         raw_path: S2,
     ) -> Result<Self> {
-        let raw_path = raw_path.into();
-        let is_regex_path = raw_path.is_regex();
-        let path_regex = if is_regex_path {
-            Some(Self::build_regex(raw_path.as_str())?)
-        } else {
-            None
-        };
-        Ok(Self {
-            name: name.into(),
-            path: raw_path.as_str().to_owned(),
-            is_regex_path,
-            path_regex,
-            content: None,
-            severity,
-        })
+        Self::with_name_and_path(name.into(), severity, &raw_path.into())
     }
 
-    pub fn with_path_and_content<S1: Into<String>, P: Into<RawPath>, S2: AsRef<str>>(
-        name: S1,
+    pub fn with_path_and_content<S: Into<String>, P: Into<RawPath>, C: Into<RawContent>>(
+        name: S,
         severity: Severity,
         raw_path: P,
-        content: S2,
+        content: C,
     ) -> Result<Self> {
-        let raw_path = raw_path.into();
-        let is_regex_path = raw_path.is_regex();
-        let path_regex = if is_regex_path {
-            Some(Self::build_regex(raw_path.as_str())?)
-        } else {
-            None
-        };
-        Ok(Self {
-            name: name.into(),
+        let name = name.into();
+        let raw_content = content.into();
+        let content = Self::build_content(&name, &raw_content, RegexFlags::default())?;
+        Ok(PatternRule {
+            content: Some(content),
+            raw_content: Some(raw_content),
+            ..Self::with_name_and_path(name, severity, &raw_path.into())?
+        })
+    }
+
+    fn from_raw_rule(rule: &RawRule) -> Result<PatternRule> {
+        Self::validate_read_limit(rule.name(), rule.read_limit().apply(ReadLimit::default()))?;
+        let hashes = Self::build_hash_list(rule.name(), rule.hashes(), rule.hash_files())?;
+        let file_condition = Self::build_file_condition(rule.name(), rule.file_condition())?;
+        Self::validate_heuristics(rule.name(), rule.heuristics())?;
+        let content = Self::build_content_pattern(
+            rule.name(),
+            rule.content(),
+            rule.content_hex(),
+            rule.flags(),
+        )?;
+
+        Ok(PatternRule {
+            match_on: rule.match_on(),
+            extensions: Self::normalize_extensions(rule.extensions()),
+            any_extension: rule.any_extension(),
+            content,
+            raw_content: rule.content().cloned(),
+            raw_content_hex: rule.content_hex().cloned(),
+            raw_hashes: rule.hashes().to_vec(),
+            hash_files: rule.hash_files().to_vec(),
+            hashes,
+            heuristics: rule.heuristics(),
+            raw_file_condition: rule.file_condition().clone(),
+            file_condition,
+            flags: rule.flags(),
+            read_limit: rule.read_limit(),
+            metadata: rule.metadata().clone(),
+            examples: rule.examples().clone(),
+            ..Self::with_name_and_path(rule.name().to_owned(), rule.severity(), rule.raw_path())?
+        })
+    }
+
+    /// Build a Rule matching the `raw_path` with the default values for all other settings
+    fn with_name_and_path(name: String, severity: Severity, raw_path: &RawPath) -> Result<Self> {
+        Ok(PatternRule {
+            name,
             path: raw_path.as_str().to_owned(),
-            is_regex_path,
-            path_regex,
-            content: Some(Self::build_regex(content.as_ref())?),
+            is_regex_path: raw_path.is_regex(),
+            path_regex: Self::build_path_regex(raw_path)?,
+            path_glob: Self::build_path_glob(raw_path)?,
+            case_sensitive_path: raw_path.is_case_sensitive(),
+            match_on: MatchTarget::default(),
+            extensions: vec![],
+            any_extension: false,
+            content: None,
+            raw_content: None,
+            raw_content_hex: None,
+            raw_hashes: vec![],
+            hash_files: vec![],
+            hashes: None,
+            heuristics: Heuristics::default(),
+            raw_file_condition: RawFileCondition::default(),
+            file_condition: None,
+            flags: RegexFlags::default(),
+            read_limit: RawReadLimit::default(),
+            default_read_limit: ReadLimit::default(),
             severity,
+            metadata: RuleMetadata::default(),
+            examples: RuleExamples::default(),
+            source: None,
         })
     }
 
-    fn from_raw_rule(rule: &RawRule) -> Result<PatternRule> {
-        let content = match rule.content() {
-            None => None,
-            Some(pattern) => Some(Self::build_regex(&pattern)?),
-        };
+    /// Return the Rule with the content patterns compiled using the given flags
+    ///
+    /// The flags do not apply to the path, its case-sensitivity is defined by the `RawPath`
+    pub fn with_flags(mut self, flags: RegexFlags) -> Result<Self> {
+        self.content = Self::build_content_pattern(
+            &self.name,
+            self.raw_content.as_ref(),
+            self.raw_content_hex.as_ref(),
+            flags,
+        )?;
+        self.flags = flags;
+
+        Ok(self)
+    }
+
+    /// Return the Rule matching the path pattern against the given part of the entry's path
+    pub fn with_match_on(mut self, match_on: MatchTarget) -> Self {
+        self.match_on = match_on;
+        self
+    }
+
+    /// Return the Rule requiring one of the given file extensions (case-insensitive)
+    ///
+    /// By default only the last extension is checked, so `shell.php.txt` does not match `php`
+    pub fn with_extensions<S: AsRef<str>>(mut self, extensions: &[S]) -> Self {
+        self.extensions = Self::normalize_extensions(extensions);
+        self
+    }
 
-        if rule.is_regex_path() {
-            Ok(PatternRule {
-                name: rule.name().to_owned(),
-                path: rule.path_ref().to_owned(),
-                is_regex_path: true,
-                path_regex: Some(Self::build_regex(rule.path_ref())?),
-                content,
-                severity: rule.severity(),
-                // rule: rule.to_owned(),
+    /// Return the Rule checking any extension of the file name (e.g. `php` in `shell.php.jpg`)
+    pub fn with_any_extension(mut self, any_extension: bool) -> Self {
+        self.any_extension = any_extension;
+        self
+    }
+
+    /// Return the extensions lowercase and without leading dot
+    fn normalize_extensions<S: AsRef<str>>(extensions: &[S]) -> Vec<String> {
+        extensions
+            .iter()
+            .map(|extension| {
+                extension
+                    .as_ref()
+                    .trim()
+                    .trim_start_matches('.')
+                    .to_lowercase()
             })
+            .filter(|extension| !extension.is_empty())
+            .collect()
+    }
+
+    /// Return the Rule with the given hex encoded byte patterns
+    ///
+    /// If the Rule also has a `content` condition, both have to match
+    pub fn with_content_hex<C: Into<RawContent>>(mut self, content_hex: C) -> Result<Self> {
+        let content_hex = content_hex.into();
+        self.content = Self::build_content_pattern(
+            &self.name,
+            self.raw_content.as_ref(),
+            Some(&content_hex),
+            self.flags,
+        )?;
+        self.raw_content_hex = Some(content_hex);
+
+        Ok(self)
+    }
+
+    /// Return the Rule matching files whose content hash is one of the given MD5, SHA-1 or
+    /// SHA-256 hashes
+    ///
+    /// If the Rule also has a `content` condition, both have to match
+    pub fn with_hashes<S: AsRef<str>>(mut self, hashes: &[S]) -> Result<Self> {
+        self.raw_hashes = hashes.iter().map(|h| h.as_ref().to_owned()).collect();
+        self.hashes = Self::build_hash_list(&self.name, &self.raw_hashes, &self.hash_files)?;
+
+        Ok(self)
+    }
+
+    /// Return the Rule matching files whose content hash is listed in one of the hash list files
+    pub fn with_hash_files<P: AsRef<Path>>(mut self, hash_files: &[P]) -> Result<Self> {
+        self.hash_files = hash_files.iter().map(|f| f.as_ref().to_owned()).collect();
+        self.hashes = Self::build_hash_list(&self.name, &self.raw_hashes, &self.hash_files)?;
+
+        Ok(self)
+    }
+
+    /// Return the hashes of file contents the Rule matches (`None` if the Rule has no hash
+    /// condition)
+    pub fn hashes(&self) -> Option<&HashList> {
+        self.hashes.as_deref()
+    }
+
+    /// Return if the Rule has a condition on the hash of the file content
+    pub fn has_hashes(&self) -> bool {
+        self.hashes.is_some()
+    }
+
+    /// Return the hashes as defined in the rule file
+    pub fn raw_hashes(&self) -> &[String] {
+        &self.raw_hashes
+    }
+
+    /// Return the paths of the hash list files
+    pub fn hash_files(&self) -> &[PathBuf] {
+        &self.hash_files
+    }
+
+    /// Return if the Rule whitelists files with known-good content hashes
+    ///
+    /// These Rules are applied before all other Rules
+    pub fn is_hash_allowlist(&self) -> bool {
+        self.has_hashes() && self.severity == Severity::WHITELIST
+    }
+
+    /// Build the list of the `hashes` and the hashes read from the `hash_files`
+    fn build_hash_list(
+        name: &str,
+        hashes: &[String],
+        hash_files: &[PathBuf],
+    ) -> Result<Option<Arc<HashList>>> {
+        if hashes.is_empty() && hash_files.is_empty() {
+            return Ok(None);
+        }
+
+        let rule_error = |e: Error| -> Error {
+            ErrorKind::RuleError(format!("Hash list of rule '{}' is invalid: {}", name, e)).into()
+        };
+        let mut list = HashList::from_hashes(hashes).map_err(rule_error)?;
+        for hash_file in hash_files {
+            list.read_file(hash_file).map_err(rule_error)?;
+        }
+        if list.is_empty() {
+            bail!(ErrorKind::RuleError(format!(
+                "Hash list of rule '{}' does not contain any hash",
+                name
+            )));
+        }
+
+        Ok(Some(Arc::new(list)))
+    }
+
+    /// Return the Rule matching files whose content metrics reach the given thresholds
+    ///
+    /// If the Rule also has a `content` condition, both have to match
+    pub fn with_heuristics(mut self, heuristics: Heuristics) -> Result<Self> {
+        Self::validate_heuristics(&self.name, heuristics)?;
+        self.heuristics = heuristics;
+
+        Ok(self)
+    }
+
+    /// Return the thresholds of the content metrics (`None` if the Rule has no heuristics)
+    pub fn heuristics(&self) -> Option<&Heuristics> {
+        if self.heuristics.is_empty() {
+            None
         } else {
-            Ok(PatternRule {
-                name: rule.name().to_owned(),
-                path: rule.path_ref().to_owned(),
-                is_regex_path: false,
-                path_regex: None,
-                content,
-                severity: rule.severity(),
-                // rule: rule.to_owned(),
-            })
+            Some(&self.heuristics)
+        }
+    }
+
+    /// Return if the Rule has thresholds of the content metrics
+    pub fn has_heuristics(&self) -> bool {
+        !self.heuristics.is_empty()
+    }
+
+    fn validate_heuristics(name: &str, heuristics: Heuristics) -> Result<()> {
+        if let Err(e) = heuristics.validate() {
+            bail!(ErrorKind::RuleError(format!(
+                "Heuristics of rule '{}' are invalid: {}",
+                name, e
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Return the Rule with the given conditions on the file's size, permissions, owner and
+    /// timestamps
+    ///
+    /// The conditions have to be fulfilled in addition to the path and content conditions
+    pub fn with_file_condition(mut self, raw_file_condition: RawFileCondition) -> Result<Self> {
+        self.file_condition = Self::build_file_condition(&self.name, &raw_file_condition)?;
+        self.raw_file_condition = raw_file_condition;
+
+        Ok(self)
+    }
+
+    /// Return the conditions on the file's metadata (`None` if the Rule has no such condition)
+    pub fn file_condition(&self) -> Option<&FileCondition> {
+        self.file_condition.as_ref()
+    }
+
+    /// Return if the Rule has a condition on the file's metadata
+    pub fn has_file_condition(&self) -> bool {
+        self.file_condition.is_some()
+    }
+
+    /// Return the conditions on the file's metadata as defined in the rule file
+    pub fn raw_file_condition(&self) -> &RawFileCondition {
+        &self.raw_file_condition
+    }
+
+    fn build_file_condition(
+        name: &str,
+        raw_file_condition: &RawFileCondition,
+    ) -> Result<Option<FileCondition>> {
+        if raw_file_condition.is_empty() {
+            return Ok(None);
+        }
+        match FileCondition::try_from(raw_file_condition) {
+            Ok(file_condition) => Ok(Some(file_condition)),
+            Err(e) => bail!(ErrorKind::RuleError(format!(
+                "File condition of rule '{}' is invalid: {}",
+                name, e
+            ))),
         }
     }
 
-    fn build_regex(pattern: &str) -> Result<Regex> {
-        RegexCache::build(pattern)
+    /// Return the flags the patterns are compiled with
+    pub fn flags(&self) -> RegexFlags {
+        self.flags
+    }
+
+    /// Return the Rule with the given read limit overrides
+    ///
+    /// Fields that are not defined in `read_limit` are taken from the default read limit
+    pub fn with_read_limit(mut self, read_limit: RawReadLimit) -> Result<Self> {
+        Self::validate_read_limit(&self.name, read_limit.apply(self.default_read_limit))?;
+        self.read_limit = read_limit;
+
+        Ok(self)
+    }
+
+    /// Return the Rule with the given default read limit (e.g. the one set on the command line)
+    ///
+    /// The read limit overrides of the Rule itself take precedence
+    pub fn with_default_read_limit(mut self, default_read_limit: ReadLimit) -> Result<Self> {
+        Self::validate_read_limit(&self.name, self.read_limit.apply(default_read_limit))?;
+        self.default_read_limit = default_read_limit;
+
+        Ok(self)
+    }
+
+    /// Return the effective limit of the content read to match the Rule
+    pub fn read_limit(&self) -> ReadLimit {
+        self.read_limit.apply(self.default_read_limit)
+    }
+
+    /// Return the read limit overrides as defined in the rule file
+    pub fn raw_read_limit(&self) -> RawReadLimit {
+        self.read_limit
+    }
+
+    fn validate_read_limit(name: &str, read_limit: ReadLimit) -> Result<()> {
+        if let Err(e) = read_limit.validate() {
+            bail!(ErrorKind::RuleError(format!(
+                "Read limit of rule '{}' is invalid: {}",
+                name, e
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Return the Rule with the given examples
+    pub fn with_examples(mut self, examples: RuleExamples) -> Self {
+        self.examples = examples;
+        self
+    }
+
+    /// Return the samples the Rule must or must not match
+    pub fn examples(&self) -> &RuleExamples {
+        &self.examples
+    }
+
+    /// Return the Rule with the given metadata
+    pub fn with_metadata(mut self, metadata: RuleMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Return the Rule with the given severity
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Return the Rule with the given description
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.metadata.description = Some(description.into());
+        self
+    }
+
+    /// Return the descriptive information about the Rule
+    pub fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    /// Return the Rule with the path of the file it was read from
+    pub fn with_source<P: Into<PathBuf>>(mut self, source: P) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Return the path of the file the Rule was read from (`None` for builtin rules)
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    /// Return the path pattern as defined in the rule file (without compilation flags)
+    pub fn raw_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Return if the regular expression or glob of the path matches letters only with their exact
+    /// casing (plain paths are always case-sensitive)
+    pub fn is_case_sensitive_path(&self) -> bool {
+        self.case_sensitive_path || self.path_kind() == RawPathKind::String
+    }
+
+    /// Return the kind of the Rule's path pattern
+    pub fn path_kind(&self) -> RawPathKind {
+        if self.is_regex_path {
+            RawPathKind::Regex
+        } else if self.path_glob.is_some() {
+            RawPathKind::Glob
+        } else {
+            RawPathKind::String
+        }
+    }
+
+    /// Return the content condition as defined in the rule file (without compilation flags)
+    pub fn raw_content(&self) -> Option<&RawContent> {
+        self.raw_content.as_ref()
+    }
+
+    /// Return the hex encoded byte patterns as defined in the rule file
+    pub fn raw_content_hex(&self) -> Option<&RawContent> {
+        self.raw_content_hex.as_ref()
+    }
+
+    /// Transform the Rule back into the rule file representation
+    pub(crate) fn to_raw_rule(&self) -> RawRule {
+        RawRule::new(
+            self.name.clone(),
+            RawPath::with_kind(self.path.clone(), self.path_kind())
+                .with_case_sensitive(self.case_sensitive_path),
+            self.severity,
+        )
+        .with_match_on(self.match_on)
+        .with_extensions(self.extensions.clone(), self.any_extension)
+        .with_content(
+            self.raw_content.clone(),
+            self.raw_content_hex.clone(),
+            self.flags,
+        )
+        .with_hashes(self.raw_hashes.clone(), self.hash_files.clone())
+        .with_heuristics(self.heuristics)
+        .with_file_condition(self.raw_file_condition.clone())
+        .with_read_limit(self.read_limit)
+        .with_metadata(self.metadata.clone(), self.examples.clone())
+    }
+
+    /// Build the content condition from the text patterns and the hex encoded byte patterns
+    fn build_content_pattern(
+        name: &str,
+        raw_content: Option<&RawContent>,
+        raw_content_hex: Option<&RawContent>,
+        flags: RegexFlags,
+    ) -> Result<Option<ContentPattern>> {
+        let content = match raw_content {
+            Some(raw_content) => Some(Self::build_content(name, raw_content, flags)?),
+            None => None,
+        };
+        let content_hex = match raw_content_hex {
+            Some(raw_content_hex) => {
+                let converted = Self::convert_hex_content(name, raw_content_hex)?;
+                Some(Self::build_content(name, &converted, flags)?)
+            }
+            None => None,
+        };
+
+        Ok(match (content, content_hex) {
+            (Some(content), Some(content_hex)) => Some(ContentPattern::Group(ContentGroup::new(
+                vec![content, content_hex],
+                vec![],
+                vec![],
+            ))),
+            (content, content_hex) => content.or(content_hex),
+        })
+    }
+
+    /// Replace the hex strings in `raw_content_hex` with regular expressions matching the bytes
+    fn convert_hex_content(name: &str, raw_content_hex: &RawContent) -> Result<RawContent> {
+        match raw_content_hex {
+            RawContent::Pattern(hex) => {
+                Ok(RawContent::Pattern(Self::build_hex_pattern(name, hex)?))
+            }
+            RawContent::Group(group) => {
+                let convert_all = |patterns: &[RawContent]| -> Result<Vec<RawContent>> {
+                    patterns
+                        .iter()
+                        .map(|pattern| Self::convert_hex_content(name, pattern))
+                        .collect()
+                };
+
+                Ok(RawContent::Group(RawContentGroup::new(
+                    convert_all(group.all_patterns())?,
+                    convert_all(group.any_patterns())?,
+                    convert_all(group.none_patterns())?,
+                )))
+            }
+        }
+    }
+
+    /// Build a regular expression matching the bytes of the `hex` string (e.g. `7f 45 4c 46`)
+    ///
+    /// Whitespace is ignored and `??` matches any byte. The bytes are matched exactly, independent
+    /// of the Rule's flags
+    pub(super) fn build_hex_pattern(name: &str, hex: &str) -> Result<String> {
+        let digits: Vec<char> = hex.chars().filter(|c| !c.is_whitespace()).collect();
+        if digits.is_empty() || !digits.len().is_multiple_of(2) {
+            bail!(ErrorKind::RuleError(format!(
+                "Hex pattern '{}' of rule '{}' must consist of pairs of hex digits",
+                hex, name
+            )));
+        }
+
+        let mut pattern = String::from("(?s-iu:");
+        for pair in digits.chunks(2) {
+            if pair == ['?', '?'] {
+                pattern.push('.');
+                continue;
+            }
+            let byte: String = pair.iter().collect();
+            match u8::from_str_radix(&byte, 16) {
+                Ok(byte) => pattern.push_str(&format!("\\x{:02x}", byte)),
+                Err(_) => bail!(ErrorKind::RuleError(format!(
+                    "Invalid byte '{}' in hex pattern '{}' of rule '{}'",
+                    byte, hex, name
+                ))),
+            }
+        }
+        pattern.push(')');
+
+        Ok(pattern)
+    }
+
+    fn build_content(
+        name: &str,
+        raw_content: &RawContent,
+        flags: RegexFlags,
+    ) -> Result<ContentPattern> {
+        match raw_content {
+            RawContent::Pattern(pattern) => Ok(ContentPattern::Regex(RegexCache::build_bytes(
+                pattern, flags,
+            )?)),
+            RawContent::Group(group) => {
+                if group.is_empty() {
+                    bail!(ErrorKind::RuleError(format!(
+                        "Content group of rule '{}' does not contain any pattern",
+                        name
+                    )));
+                }
+                let build_all = |patterns: &[RawContent]| -> Result<Vec<ContentPattern>> {
+                    patterns
+                        .iter()
+                        .map(|pattern| Self::build_content(name, pattern, flags))
+                        .collect()
+                };
+
+                Ok(ContentPattern::Group(ContentGroup::new(
+                    build_all(group.all_patterns())?,
+                    build_all(group.any_patterns())?,
+                    build_all(group.none_patterns())?,
+                )))
+            }
+        }
+    }
+
+    fn build_path_regex(raw_path: &RawPath) -> Result<Option<Regex>> {
+        if !raw_path.is_regex() {
+            return Ok(None);
+        }
+        let flags = RegexFlags {
+            case_sensitive: raw_path.is_case_sensitive(),
+            ..RegexFlags::default()
+        };
+
+        Ok(Some(Self::build_regex(raw_path.as_str(), flags)?))
+    }
+
+    fn build_path_glob(raw_path: &RawPath) -> Result<Option<GlobMatcher>> {
+        if !raw_path.is_glob() {
+            return Ok(None);
+        }
+
+        // Globs that are not anchored at the root directory may match in any directory
+        let pattern = raw_path.as_str();
+        let pattern = if pattern.starts_with('/') || pattern.starts_with("**") {
+            pattern.to_owned()
+        } else {
+            format!("**/{}", pattern)
+        };
+        let glob = GlobBuilder::new(&pattern)
+            .case_insensitive(!raw_path.is_case_sensitive())
+            .literal_separator(true)
+            .build()?;
+
+        Ok(Some(glob.compile_matcher()))
+    }
+
+    fn build_regex(pattern: &str, flags: RegexFlags) -> Result<Regex> {
+        RegexCache::build(pattern, flags)
     }
 }
 
@@ -190,19 +764,32 @@ impl PatternRule {
 //     false
 // }
 
-impl RuleTrait<Regex> for PatternRule {
+impl RuleTrait<ContentPattern> for PatternRule {
     fn name(&self) -> &str {
         self.name.as_str()
     }
 
     fn path(&self) -> RulePath {
-        match &self.path_regex {
-            Some(p) => RulePath::Regex(p.clone()),
-            None => RulePath::String(self.path.clone()),
+        match (&self.path_regex, &self.path_glob) {
+            (Some(p), _) => RulePath::Regex(p.clone()),
+            (None, Some(g)) => RulePath::Glob(g.clone()),
+            (None, None) => RulePath::String(self.path.clone()),
         }
     }
 
-    fn content(&self) -> Option<Regex> {
+    fn match_on(&self) -> MatchTarget {
+        self.match_on
+    }
+
+    fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    fn any_extension(&self) -> bool {
+        self.any_extension
+    }
+
+    fn content(&self) -> Option<ContentPattern> {
         self.content.clone()
     }
 
@@ -214,6 +801,26 @@ impl RuleTrait<Regex> for PatternRule {
     fn is_regex_path(&self) -> bool {
         self.is_regex_path
     }
+
+    fn id(&self) -> &str {
+        self.metadata.id.as_deref().unwrap_or(&self.name)
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.metadata.description.as_deref()
+    }
+
+    fn references(&self) -> &[String] {
+        &self.metadata.references
+    }
+
+    fn tags(&self) -> &[String] {
+        &self.metadata.tags
+    }
+
+    fn author(&self) -> Option<&str> {
+        self.metadata.author.as_deref()
+    }
 }
 
 impl TryFrom<RawRule> for PatternRule {
@@ -228,6 +835,7 @@ impl TryFrom<RawRule> for PatternRule {
 mod test {
     use super::*;
     use crate::rule::raw_rule::RawPath;
+    use crate::rule::RawContentGroup;
 
     #[test]
     fn from_rule_test() {
@@ -263,4 +871,145 @@ mod test {
         assert!(pattern_rule.content().is_some());
         assert!(pattern_rule.content().unwrap().is_match("2014-01-01"));
     }
+
+    #[test]
+    fn with_glob_path_test() {
+        let pattern_rule = PatternRule::with_path(
+            "7",
+            Severity::MAJOR,
+            RawPath::with_glob("typo3conf/ext/*/Resources/Public/**/*.php"),
+        )
+        .unwrap();
+        assert!(!pattern_rule.is_regex_path());
+        let glob = pattern_rule.path();
+        assert_eq!(
+            "**/typo3conf/ext/*/Resources/Public/**/*.php",
+            glob.to_string()
+        );
+        let glob = glob.glob();
+        assert!(glob.is_match("/var/www/typo3conf/ext/news/Resources/Public/shell.php"));
+        assert!(glob.is_match("/var/www/typo3conf/ext/news/Resources/Public/Js/x/shell.PHP"));
+        assert!(!glob.is_match("/var/www/typo3conf/ext/news/Classes/Resources/Public/a.php"));
+        assert!(!glob.is_match("/var/www/typo3conf/ext/news/Resources/Public/a.php.txt"));
+
+        let pattern_rule =
+            PatternRule::with_path("8", Severity::MAJOR, RawPath::with_glob("/var/*.php")).unwrap();
+        assert!(pattern_rule.path().glob().is_match("/var/a.php"));
+        assert!(!pattern_rule.path().glob().is_match("/srv/var/a.php"));
+
+        assert!(PatternRule::with_path("9", Severity::MAJOR, RawPath::with_glob("a[")).is_err());
+    }
+
+    #[test]
+    fn with_flags_test() {
+        let rule = PatternRule::with_path_and_content(
+            "10",
+            Severity::MAJOR,
+            RawPath::with_glob("Uploads/*.php"),
+            r"^FilesMan.+end",
+        )
+        .unwrap();
+        assert!(rule.content().unwrap().is_match("filesman end"));
+        assert!(!rule.content().unwrap().is_match("<?php\nFilesMan\nend"));
+        assert!(rule.path().glob().is_match("/var/uploads/a.PHP"));
+
+        let rule = rule.with_flags(RegexFlags::new(true, true, true)).unwrap();
+        assert_eq!(RegexFlags::new(true, true, true), rule.flags());
+        let content = rule.content().unwrap();
+        assert!(!content.is_match("filesman end"));
+        assert!(content.is_match("<?php\nFilesMan\nend"));
+        // The flags of the content do not apply to the path
+        assert!(rule.path().glob().is_match("/var/uploads/a.PHP"));
+
+        let rule = PatternRule::with_path(
+            "11",
+            Severity::MAJOR,
+            RawPath::with_regex("Shell").with_case_sensitive(true),
+        )
+        .unwrap();
+        assert_eq!("Shell", rule.path().to_string());
+        assert!(rule.is_case_sensitive_path());
+        assert!(!rule.path().regex().is_match("/var/www/shell.php"));
+        assert!(rule.path().regex().is_match("/var/www/Shell.php"));
+
+        let rule = PatternRule::with_path_and_content(
+            "12",
+            Severity::MAJOR,
+            RawPath::with_regex(r"\.php$"),
+            "FilesMan",
+        )
+        .unwrap()
+        .with_flags(RegexFlags::new(true, false, false))
+        .unwrap();
+        assert!(!rule.content().unwrap().is_match("filesman"));
+        assert!(rule.path().regex().is_match("/var/www/shell.PHP"));
+        assert_eq!(
+            RawPath::with_regex(r"\.php$"),
+            *rule.to_raw_rule().raw_path()
+        );
+    }
+
+    #[test]
+    fn with_content_hex_test() {
+        let elf = b"\x7fELF\x02\x01\x01\x00\xff\xfe";
+        let rule = PatternRule::with_path("12", Severity::MAJOR, RawPath::with_path(".bin"))
+            .unwrap()
+            .with_content_hex("7f 45 4C 46 ?? 01")
+            .unwrap();
+        let content = rule.content().unwrap();
+        assert_eq!(r"(?i)(?s-iu:\x7f\x45\x4c\x46.\x01)", content.to_string());
+        assert!(content.is_match(&elf[..]));
+        assert!(!content.is_match(b"\x7fElf\x02\x01"));
+        assert_eq!(
+            Some(&RawContent::from("7f 45 4C 46 ?? 01")),
+            rule.raw_content_hex()
+        );
+
+        // Text and byte patterns both have to match
+        let rule = PatternRule::with_path_and_content(
+            "13",
+            Severity::MAJOR,
+            RawPath::with_path(".bin"),
+            "eval",
+        )
+        .unwrap()
+        .with_content_hex(RawContentGroup::any(vec!["4d5a".into(), "7f454c46".into()]))
+        .unwrap();
+        let content = rule.content().unwrap();
+        assert!(content.is_match(b"\x7fELF\xff eval("));
+        assert!(!content.is_match(&elf[..]));
+        assert!(!content.is_match("eval("));
+
+        let rule = || PatternRule::with_path("14", Severity::MAJOR, RawPath::with_path(".bin"));
+        assert!(rule().unwrap().with_content_hex("7f4").is_err());
+        assert!(rule().unwrap().with_content_hex("7g").is_err());
+        assert!(rule().unwrap().with_content_hex(" ").is_err());
+    }
+
+    #[test]
+    fn with_path_and_content_group_test() {
+        let pattern_rule = PatternRule::with_path_and_content(
+            "5",
+            Severity::MAJOR,
+            RawPath::with_path(".php"),
+            RawContentGroup::all(vec!["base64_decode".into(), "eval".into()])
+                .with_none(vec!["@license".into()]),
+        )
+        .unwrap();
+        let content = pattern_rule.content().unwrap();
+        assert_eq!(
+            "all((?i)base64_decode, (?i)eval) none((?i)@license)",
+            content.to_string()
+        );
+        assert!(content.is_match("eval(base64_decode('...'))"));
+        assert!(!content.is_match("/* @license */ eval(base64_decode('...'))"));
+
+        let result = PatternRule::with_path_and_content(
+            "6",
+            Severity::MAJOR,
+            RawPath::with_path(".php"),
+            RawContentGroup::default(),
+        );
+        assert!(result.is_err());
+    }
 }
@@ -1,38 +1,186 @@
 use crate::errors::*;
+use crate::rule::RegexFlags;
 use lazy_static::lazy_static;
+use regex::bytes::Regex as BytesRegex;
 use regex::Regex;
+use regex_syntax::ast::{self, Ast};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use regex_syntax::ParserBuilder;
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::sync::RwLock;
 
-type RegexCacheMap = HashMap<String, Regex>;
+/// The same pattern compiled with different flags results in different regular expressions
+type RegexCacheKey = (String, RegexFlags);
+type RegexCacheMap<R> = HashMap<RegexCacheKey, R>;
 lazy_static! {
-    static ref REGEX_CACHE: RwLock<RegexCacheMap> = RwLock::new(HashMap::new());
+    static ref REGEX_CACHE: RwLock<RegexCacheMap<Regex>> = RwLock::new(HashMap::new());
+    static ref BYTES_REGEX_CACHE: RwLock<RegexCacheMap<BytesRegex>> = RwLock::new(HashMap::new());
 }
 
 pub struct RegexCache;
 
 impl RegexCache {
-    pub fn build(pattern: &str) -> Result<Regex> {
-        if let Some(regex) = RegexCache::get(pattern) {
+    /// Build a regular expression to match paths
+    pub fn build(pattern: &str, flags: RegexFlags) -> Result<Regex> {
+        RegexCache::build_cached(&REGEX_CACHE, pattern, flags, Regex::new)
+    }
+
+    /// Build a regular expression to match the raw bytes of file contents
+    ///
+    /// Classes that match the replacement character U+FFFD (e.g. `.`, `\W` or `[^"]`) also match
+    /// bytes that are not valid UTF-8, like they did when the content was converted lossily
+    pub fn build_bytes(pattern: &str, flags: RegexFlags) -> Result<BytesRegex> {
+        RegexCache::build_cached(&BYTES_REGEX_CACHE, pattern, flags, |pattern| {
+            BytesRegex::new(&match_invalid_utf8(pattern))
+        })
+    }
+
+    fn build_cached<R: Clone, F>(
+        cache: &RwLock<RegexCacheMap<R>>,
+        pattern: &str,
+        flags: RegexFlags,
+        compile: F,
+    ) -> Result<R>
+    where
+        F: Fn(&str) -> std::result::Result<R, regex::Error>,
+    {
+        let key = (pattern.to_owned(), flags);
+        if let Some(regex) = RegexCache::get(cache, &key) {
             return Ok(regex);
         }
 
-        let regex = Regex::new(&format!("(?i){}", pattern))?;
-        RegexCache::set(pattern, regex.clone());
+        let regex = compile(&format!("{}{}", flags.to_inline_flags(), pattern))?;
+        RegexCache::set(cache, key, regex.clone());
 
         Ok(regex)
     }
 
-    fn get(pattern: &str) -> Option<Regex> {
-        match REGEX_CACHE.read() {
-            Ok(read_handle) => (*read_handle).get(pattern).cloned(),
+    fn get<K: Eq + Hash, R: Clone>(cache: &RwLock<HashMap<K, R>>, key: &K) -> Option<R> {
+        match cache.read() {
+            Ok(read_handle) => (*read_handle).get(key).cloned(),
             Err(_) => None,
         }
     }
 
-    fn set(pattern: &str, regex: Regex) {
-        if let Ok(mut write_handle) = REGEX_CACHE.try_write() {
-            (*write_handle).insert(pattern.to_owned(), regex);
+    fn set<K: Eq + Hash, R>(cache: &RwLock<HashMap<K, R>>, key: K, regex: R) {
+        if let Ok(mut write_handle) = cache.try_write() {
+            (*write_handle).insert(key, regex);
+        }
+    }
+}
+
+/// Return the `pattern` with every class that matches U+FFFD extended to match the bytes
+/// `\x80-\xFF`
+///
+/// Patterns that can not be parsed or that do not contain such a class are returned unchanged
+fn match_invalid_utf8(pattern: &str) -> String {
+    let parser = || ParserBuilder::new().utf8(false).build();
+    if !parser()
+        .parse(pattern)
+        .is_ok_and(|hir| matches_replacement_character(&hir))
+    {
+        return pattern.to_owned();
+    }
+    let ast = match ast::parse::Parser::new().parse(pattern) {
+        Ok(ast) => ast,
+        Err(_) => return pattern.to_owned(),
+    };
+
+    let mut spans = vec![];
+    collect_class_spans(&ast, &mut spans);
+    let mut result = String::with_capacity(pattern.len());
+    let mut end = 0;
+    for span in spans {
+        let class = &pattern[span.start.offset..span.end.offset];
+        if !parser()
+            .parse(class)
+            .is_ok_and(|hir| matches_replacement_character(&hir))
+        {
+            continue;
+        }
+        result.push_str(&pattern[end..span.start.offset]);
+        result.push_str(&format!("(?:{}|(?-u:[\\x80-\\xFF]))", class));
+        end = span.end.offset;
+    }
+    result.push_str(&pattern[end..]);
+
+    result
+}
+
+/// Collect the spans of the outermost classes (including `.`) in `ast`
+fn collect_class_spans(ast: &Ast, spans: &mut Vec<ast::Span>) {
+    match ast {
+        Ast::Dot(span) => spans.push(**span),
+        Ast::ClassPerl(class) => spans.push(class.span),
+        Ast::ClassUnicode(class) => spans.push(class.span),
+        Ast::ClassBracketed(class) => spans.push(class.span),
+        Ast::Repetition(repetition) => collect_class_spans(&repetition.ast, spans),
+        Ast::Group(group) => collect_class_spans(&group.ast, spans),
+        Ast::Alternation(alternation) => alternation
+            .asts
+            .iter()
+            .for_each(|ast| collect_class_spans(ast, spans)),
+        Ast::Concat(concat) => concat
+            .asts
+            .iter()
+            .for_each(|ast| collect_class_spans(ast, spans)),
+        _ => {}
+    }
+}
+
+/// Return if `hir` contains a Unicode class that matches U+FFFD
+fn matches_replacement_character(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Class(Class::Unicode(class)) => class
+            .ranges()
+            .iter()
+            .any(|range| range.start() <= '\u{FFFD}' && '\u{FFFD}' <= range.end()),
+        HirKind::Repetition(repetition) => matches_replacement_character(&repetition.sub),
+        HirKind::Capture(capture) => matches_replacement_character(&capture.sub),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => {
+            hirs.iter().any(matches_replacement_character)
         }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn build_with_flags_test() {
+        let insensitive = RegexCache::build("EvAl", RegexFlags::default()).unwrap();
+        let sensitive = RegexCache::build("EvAl", RegexFlags::new(true, false, false)).unwrap();
+
+        assert!(insensitive.is_match("eval"));
+        assert!(!sensitive.is_match("eval"));
+        assert!(sensitive.is_match("EvAl"));
+    }
+
+    #[test]
+    fn build_bytes_test() {
+        let regex = RegexCache::build_bytes("eval", RegexFlags::default()).unwrap();
+
+        assert!(regex.is_match(b"\xff\xfe EVAL \x00"));
+    }
+
+    #[test]
+    fn build_bytes_invalid_utf8_test() {
+        let regex = RegexCache::build_bytes(r#"eval\("[^"]+"\)"#, RegexFlags::default()).unwrap();
+        assert!(regex.is_match(b"eval(\"\xff\xfe\")"));
+        assert!(regex.is_match("eval(\"€\")".as_bytes()));
+        assert!(!regex.is_match(b"eval(\"\")"));
+
+        let regex = RegexCache::build_bytes(r"a.b\Wc", RegexFlags::default()).unwrap();
+        assert!(regex.is_match(b"a\x80b\xffc"));
+        assert!(regex.is_match("aöb c".as_bytes()));
+        assert!(!regex.is_match(b"a\nb c"));
+
+        // Classes not matching U+FFFD and byte patterns are not changed
+        assert_eq!(r"\w+\s[a-z]", match_invalid_utf8(r"\w+\s[a-z]"));
+        assert_eq!(r"(?s-u:\x7f.)", match_invalid_utf8(r"(?s-u:\x7f.)"));
+        assert_eq!(r"(?:[^a]|(?-u:[\x80-\xFF]))+", match_invalid_utf8(r"[^a]+"));
     }
 }
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// "raw" content condition of a Rule
+///
+/// `content` can be specified either as a string containing a single pattern, or an object with
+/// `all`, `any` and `none` lists of patterns (or nested objects)
+#[derive(Debug, Clone, Deserialize, Serialize, PartialOrd, PartialEq)]
+#[serde(untagged)]
+pub enum RawContent {
+    Pattern(String),
+    Group(RawContentGroup),
+}
+
+/// "raw" group of content patterns
+///
+/// The group matches if every pattern in `all`, at least one pattern in `any` (if not empty) and
+/// no pattern in `none` matches
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialOrd, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RawContentGroup {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    all: Vec<RawContent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    any: Vec<RawContent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    none: Vec<RawContent>,
+}
+
+impl RawContentGroup {
+    pub fn new(all: Vec<RawContent>, any: Vec<RawContent>, none: Vec<RawContent>) -> Self {
+        Self { all, any, none }
+    }
+
+    /// Build a group that matches if all of the `patterns` match
+    pub fn all(patterns: Vec<RawContent>) -> Self {
+        Self::new(patterns, vec![], vec![])
+    }
+
+    /// Build a group that matches if at least one of the `patterns` matches
+    pub fn any(patterns: Vec<RawContent>) -> Self {
+        Self::new(vec![], patterns, vec![])
+    }
+
+    /// Build a group that matches if none of the `patterns` matches
+    pub fn none(patterns: Vec<RawContent>) -> Self {
+        Self::new(vec![], vec![], patterns)
+    }
+
+    /// Return the group with the additional patterns that must not match
+    pub fn with_none(mut self, patterns: Vec<RawContent>) -> Self {
+        self.none = patterns;
+        self
+    }
+
+    pub fn all_patterns(&self) -> &[RawContent] {
+        &self.all
+    }
+
+    pub fn any_patterns(&self) -> &[RawContent] {
+        &self.any
+    }
+
+    pub fn none_patterns(&self) -> &[RawContent] {
+        &self.none
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.all.is_empty() && self.any.is_empty() && self.none.is_empty()
+    }
+}
+
+impl RawContent {
+    /// Return every pattern of the condition, including the ones of nested groups
+    pub fn patterns(&self) -> Vec<&str> {
+        match self {
+            RawContent::Pattern(pattern) => vec![pattern.as_str()],
+            RawContent::Group(group) => group
+                .all
+                .iter()
+                .chain(group.any.iter())
+                .chain(group.none.iter())
+                .flat_map(RawContent::patterns)
+                .collect(),
+        }
+    }
+}
+
+impl From<&str> for RawContent {
+    fn from(pattern: &str) -> Self {
+        RawContent::Pattern(pattern.to_owned())
+    }
+}
+
+impl From<String> for RawContent {
+    fn from(pattern: String) -> Self {
+        RawContent::Pattern(pattern)
+    }
+}
+
+impl From<RawContentGroup> for RawContent {
+    fn from(group: RawContentGroup) -> Self {
+        RawContent::Group(group)
+    }
+}
+
+impl fmt::Display for RawContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawContent::Pattern(pattern) => write!(f, "{}", pattern),
+            RawContent::Group(group) => write!(f, "{}", group),
+        }
+    }
+}
+
+impl fmt::Display for RawContentGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (operator, patterns) in &[("all", &self.all), ("any", &self.any), ("none", &self.none)]
+        {
+            if patterns.is_empty() {
+                continue;
+            }
+            if !first {
+                write!(f, " ")?;
+            }
+            first = false;
+            let joined: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+            write!(f, "{}({})", operator, joined.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deserialize_test() {
+        let content: RawContent = serde_yaml::from_str("eval").unwrap();
+        assert_eq!(RawContent::from("eval"), content);
+
+        let content: RawContent = serde_yaml::from_str(
+            "
+all: [base64_decode, eval]
+none:
+  - '@license'
+  - any: [GPL, MIT]
+",
+        )
+        .unwrap();
+        assert_eq!(
+            RawContent::from(
+                RawContentGroup::all(vec!["base64_decode".into(), "eval".into()]).with_none(vec![
+                    "@license".into(),
+                    RawContentGroup::any(vec!["GPL".into(), "MIT".into()]).into(),
+                ])
+            ),
+            content
+        );
+
+        assert!(serde_yaml::from_str::<RawContent>("{ every: [eval] }").is_err());
+    }
+}
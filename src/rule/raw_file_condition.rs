@@ -0,0 +1,165 @@
+use super::file_condition::parse_duration;
+use super::raw_read_limit::deserialize_byte_size;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// "raw" conditions on the metadata of a file (size, permissions, owner and timestamps)
+///
+/// Sizes may be given as number of bytes or as string with a unit (e.g. `"16M"`), ages as number
+/// of seconds or as string with a unit (e.g. `"3d"`). Owner and group may be given as name or id
+#[derive(Debug, Clone, Default, PartialOrd, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RawFileCondition {
+    /// Minimum size of the file in bytes
+    #[serde(
+        default,
+        deserialize_with = "deserialize_byte_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_size: Option<u64>,
+
+    /// Maximum size of the file in bytes
+    #[serde(
+        default,
+        deserialize_with = "deserialize_byte_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_size: Option<u64>,
+
+    /// The file must (`true`) or must not (`false`) be writable by everyone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_writable: Option<bool>,
+
+    /// The file must (`true`) or must not (`false`) be executable by anyone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable: Option<bool>,
+
+    /// The file must (`true`) or must not (`false`) have the setuid bit set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setuid: Option<bool>,
+
+    /// Name or id of the user owning the file
+    #[serde(
+        default,
+        deserialize_with = "deserialize_name_or_id",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub owner: Option<String>,
+
+    /// Name or id of the group owning the file
+    #[serde(
+        default,
+        deserialize_with = "deserialize_name_or_id",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub group: Option<String>,
+
+    /// Maximum age of the modification time in seconds
+    #[serde(
+        default,
+        deserialize_with = "deserialize_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub modified_within: Option<u64>,
+
+    /// Minimum age of the modification time in seconds
+    #[serde(
+        default,
+        deserialize_with = "deserialize_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub modified_older_than: Option<u64>,
+
+    /// Maximum age of the status change time in seconds
+    #[serde(
+        default,
+        deserialize_with = "deserialize_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub changed_within: Option<u64>,
+
+    /// Minimum age of the status change time in seconds
+    #[serde(
+        default,
+        deserialize_with = "deserialize_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub changed_older_than: Option<u64>,
+}
+
+impl RawFileCondition {
+    /// Return if no condition is defined
+    pub fn is_empty(&self) -> bool {
+        self == &RawFileCondition::default()
+    }
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Duration {
+        Seconds(u64),
+        Text(String),
+    }
+
+    match Option::<Duration>::deserialize(deserializer)? {
+        Some(Duration::Seconds(seconds)) => Ok(Some(seconds)),
+        Some(Duration::Text(text)) => parse_duration(&text)
+            .map(Some)
+            .map_err(|e| D::Error::custom(e.to_string())),
+        None => Ok(None),
+    }
+}
+
+fn deserialize_name_or_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NameOrId {
+        Id(u32),
+        Name(String),
+    }
+
+    Ok(
+        Option::<NameOrId>::deserialize(deserializer)?.map(|value| match value {
+            NameOrId::Id(id) => id.to_string(),
+            NameOrId::Name(name) => name,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_empty_test() {
+        assert!(RawFileCondition::default().is_empty());
+        let raw = RawFileCondition {
+            setuid: Some(false),
+            ..RawFileCondition::default()
+        };
+        assert!(!raw.is_empty());
+    }
+
+    #[test]
+    fn deserialize_test() {
+        let raw: RawFileCondition = serde_yaml::from_str(
+            "min_size: 1K\nworld_writable: true\nowner: 33\ngroup: www-data\nmodified_within: 3d\n",
+        )
+        .unwrap();
+        assert_eq!(Some(1024), raw.min_size);
+        assert_eq!(None, raw.max_size);
+        assert_eq!(Some(true), raw.world_writable);
+        assert_eq!(Some("33".to_owned()), raw.owner);
+        assert_eq!(Some("www-data".to_owned()), raw.group);
+        assert_eq!(Some(3 * 24 * 60 * 60), raw.modified_within);
+        assert!(!raw.is_empty());
+
+        assert!(serde_yaml::from_str::<RawFileCondition>("modified_within: 3y\n").is_err());
+        assert!(serde_yaml::from_str::<RawFileCondition>("writable: true\n").is_err());
+    }
+}
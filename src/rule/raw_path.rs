@@ -1,42 +1,161 @@
+use std::convert::TryFrom;
 use std::fmt;
 use std::marker::PhantomData;
 use std::str::FromStr;
 
 use serde::de::{self, MapAccess, Visitor};
-use serde::{Deserialize, Deserializer};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Kind of a Rule's path pattern
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+pub enum RawPathKind {
+    /// The path must contain the pattern
+    String,
+    /// The path must match the regular expression
+    Regex,
+    /// The path must match the glob
+    Glob,
+}
 
 #[derive(Debug, Clone, Deserialize, PartialOrd, PartialEq)]
+#[serde(try_from = "RawPathDefinition")]
 pub struct RawPath {
     pattern: String,
-    is_regex: bool,
+    kind: RawPathKind,
+    /// Match the regular expression or glob with the exact casing (independent of the flags of
+    /// the content patterns)
+    case_sensitive: bool,
 }
 
 impl RawPath {
     pub fn new<S: Into<String>>(pattern: S, is_regex: bool) -> Self {
         Self {
             pattern: pattern.into(),
-            is_regex,
+            kind: if is_regex {
+                RawPathKind::Regex
+            } else {
+                RawPathKind::String
+            },
+            case_sensitive: false,
         }
     }
     pub fn with_regex<S: Into<String>>(pattern: S) -> Self {
         Self {
             pattern: pattern.into(),
-            is_regex: true,
+            kind: RawPathKind::Regex,
+            case_sensitive: false,
         }
     }
     pub fn with_path<S: Into<String>>(pattern: S) -> Self {
         Self {
             pattern: pattern.into(),
-            is_regex: false,
+            kind: RawPathKind::String,
+            case_sensitive: false,
+        }
+    }
+    pub fn with_kind<S: Into<String>>(pattern: S, kind: RawPathKind) -> Self {
+        Self {
+            pattern: pattern.into(),
+            kind,
+            case_sensitive: false,
         }
     }
+    pub fn with_glob<S: Into<String>>(pattern: S) -> Self {
+        Self {
+            pattern: pattern.into(),
+            kind: RawPathKind::Glob,
+            case_sensitive: false,
+        }
+    }
+
+    /// Return the path matching the regular expression or glob with the exact casing
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
 
     pub fn as_str(&self) -> &str {
         self.pattern.as_str()
     }
 
+    pub fn kind(&self) -> RawPathKind {
+        self.kind
+    }
+
     pub fn is_regex(&self) -> bool {
-        self.is_regex
+        self.kind == RawPathKind::Regex
+    }
+
+    pub fn is_glob(&self) -> bool {
+        self.kind == RawPathKind::Glob
+    }
+
+    pub fn is_case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+}
+
+impl Serialize for RawPath {
+    /// Serialize plain paths as string and regular expressions and globs as map
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.kind {
+            RawPathKind::String => serializer.serialize_str(&self.pattern),
+            RawPathKind::Regex => {
+                let mut map = serializer.serialize_map(None)?;
+                map.serialize_entry("pattern", &self.pattern)?;
+                map.serialize_entry("is_regex", &true)?;
+                if self.case_sensitive {
+                    map.serialize_entry("case_sensitive", &true)?;
+                }
+                map.end()
+            }
+            RawPathKind::Glob => {
+                let mut map = serializer.serialize_map(None)?;
+                map.serialize_entry("glob", &self.pattern)?;
+                if self.case_sensitive {
+                    map.serialize_entry("case_sensitive", &true)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Map representation of a `RawPath` in configuration files
+///
+/// Either `pattern` (with an optional `is_regex` flag) or `glob` must be given. Regular
+/// expressions and globs are case-insensitive unless `case_sensitive` is set
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPathDefinition {
+    pattern: Option<String>,
+    #[serde(default)]
+    is_regex: bool,
+    glob: Option<String>,
+    #[serde(default)]
+    case_sensitive: bool,
+}
+
+impl TryFrom<RawPathDefinition> for RawPath {
+    type Error = String;
+
+    fn try_from(value: RawPathDefinition) -> Result<Self, Self::Error> {
+        let case_sensitive = value.case_sensitive;
+        match (value.pattern, value.glob) {
+            (Some(_), None) if case_sensitive && !value.is_regex => {
+                Err("plain paths are always case-sensitive, `case_sensitive` requires `is_regex` or `glob`".to_owned())
+            }
+            (Some(pattern), None) => {
+                Ok(RawPath::new(pattern, value.is_regex).with_case_sensitive(case_sensitive))
+            }
+            (None, Some(glob)) if !value.is_regex => {
+                Ok(RawPath::with_glob(glob).with_case_sensitive(case_sensitive))
+            }
+            (None, Some(_)) => Err("`is_regex` can not be combined with `glob`".to_owned()),
+            (Some(_), Some(_)) => Err("`pattern` and `glob` are mutually exclusive".to_owned()),
+            (None, None) => Err("either `pattern` or `glob` must be defined".to_owned()),
+        }
     }
 }
 
@@ -44,7 +163,7 @@ impl RawPath {
 //     fn from(pattern: &str) -> Self {
 //         RawPath {
 //             pattern: pattern.to_owned(),
-//             is_regex: false,
+//             kind: RawPathKind::String,
 //         }
 //     }
 // }
@@ -53,15 +172,12 @@ impl RawPath {
 // the input file contains a string and not a struct.
 //
 // > `path` can be specified either as a string containing the path, or an object with the
-// > path and `is_regex` boolean
+// > path and `is_regex` boolean, or an object with a `glob`
 impl FromStr for RawPath {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(RawPath {
-            pattern: s.to_string(),
-            is_regex: false,
-        })
+        Ok(RawPath::with_path(s))
     }
 }
 
@@ -108,3 +224,40 @@ where
 
     deserializer.deserialize_any(StringOrStruct(PhantomData))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deserialize_test() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            #[serde(deserialize_with = "string_or_struct")]
+            path: RawPath,
+        }
+        let parse = |input: &str| serde_yaml::from_str::<Wrapper>(input).map(|w| w.path);
+
+        assert_eq!(
+            RawPath::with_path("some/path"),
+            parse("path: some/path").unwrap()
+        );
+        assert_eq!(
+            RawPath::with_regex("\\.php$"),
+            parse("path: { pattern: '\\.php$', is_regex: true }").unwrap()
+        );
+        assert_eq!(
+            RawPath::with_glob("**/uploads/**/*.php"),
+            parse("path: { glob: '**/uploads/**/*.php' }").unwrap()
+        );
+        assert!(parse("path: { glob: '*.php', pattern: '.php' }").is_err());
+        assert!(parse("path: { glob: '*.php', is_regex: true }").is_err());
+        assert!(parse("path: { is_regex: true }").is_err());
+
+        assert_eq!(
+            RawPath::with_glob("Uploads/*.php").with_case_sensitive(true),
+            parse("path: { glob: 'Uploads/*.php', case_sensitive: true }").unwrap()
+        );
+        assert!(parse("path: { pattern: 'Uploads', case_sensitive: true }").is_err());
+    }
+}
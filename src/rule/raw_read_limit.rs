@@ -0,0 +1,110 @@
+use super::read_limit::{parse_byte_size, ReadLimit};
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// "raw" read limit of a Rule, overriding the fields of the global read limit that are defined
+///
+/// Sizes may be given as number of bytes or as string with a unit (e.g. `"16M"`)
+#[derive(Debug, Clone, Copy, Default, PartialOrd, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RawReadLimit {
+    /// Number of bytes read from the beginning of the file (the chunk size in the chunked mode)
+    #[serde(
+        default,
+        deserialize_with = "deserialize_byte_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub head: Option<u64>,
+
+    /// Number of bytes read from the end of the file
+    #[serde(
+        default,
+        deserialize_with = "deserialize_byte_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tail: Option<u64>,
+
+    /// Stream the whole file in chunks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunked: Option<bool>,
+
+    /// Number of bytes consecutive chunks overlap
+    #[serde(
+        default,
+        deserialize_with = "deserialize_byte_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub overlap: Option<u64>,
+}
+
+impl RawReadLimit {
+    /// Return if no field is overridden
+    pub fn is_empty(&self) -> bool {
+        self == &RawReadLimit::default()
+    }
+
+    /// Return the `defaults` with the fields defined in this limit replaced
+    pub fn apply(&self, defaults: ReadLimit) -> ReadLimit {
+        ReadLimit {
+            head: self.head.unwrap_or(defaults.head),
+            tail: self.tail.unwrap_or(defaults.tail),
+            chunked: self.chunked.unwrap_or(defaults.chunked),
+            overlap: self.overlap.unwrap_or(defaults.overlap),
+        }
+    }
+}
+
+pub(super) fn deserialize_byte_size<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ByteSize {
+        Bytes(u64),
+        Text(String),
+    }
+
+    match Option::<ByteSize>::deserialize(deserializer)? {
+        Some(ByteSize::Bytes(bytes)) => Ok(Some(bytes)),
+        Some(ByteSize::Text(text)) => parse_byte_size(&text)
+            .map(Some)
+            .map_err(|e| D::Error::custom(e.to_string())),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apply_test() {
+        let defaults = ReadLimit::default();
+        assert_eq!(defaults, RawReadLimit::default().apply(defaults));
+
+        let raw = RawReadLimit {
+            tail: Some(1024),
+            chunked: Some(true),
+            ..RawReadLimit::default()
+        };
+        assert_eq!(
+            ReadLimit {
+                tail: 1024,
+                chunked: true,
+                ..defaults
+            },
+            raw.apply(defaults)
+        );
+    }
+
+    #[test]
+    fn deserialize_test() {
+        let raw: RawReadLimit = serde_yaml::from_str("head: 64K\ntail: 1024\n").unwrap();
+        assert_eq!(Some(64 * 1024), raw.head);
+        assert_eq!(Some(1024), raw.tail);
+        assert_eq!(None, raw.chunked);
+
+        assert!(serde_yaml::from_str::<RawReadLimit>("head: 4X\n").is_err());
+        assert!(serde_yaml::from_str::<RawReadLimit>("size: 4M\n").is_err());
+    }
+}
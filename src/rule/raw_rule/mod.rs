@@ -1,24 +1,150 @@
+use super::raw_content::RawContent;
 use super::raw_path::string_or_struct;
 pub(crate) use super::raw_path::RawPath;
+use crate::rule::heuristics::Heuristics;
+use crate::rule::match_target::MatchTarget;
+use crate::rule::raw_file_condition::RawFileCondition;
+use crate::rule::raw_read_limit::RawReadLimit;
+use crate::rule::regex_flags::RegexFlags;
+use crate::rule::rule_examples::RuleExamples;
+use crate::rule::rule_metadata::RuleMetadata;
 use crate::rule::rule_path::RulePath;
 use crate::severity::Severity;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 /// "raw" Rule
-#[derive(Debug, Clone, Deserialize, PartialOrd, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialOrd, PartialEq)]
 pub(crate) struct RawRule {
     name: String,
     #[serde(deserialize_with = "string_or_struct")]
     path: RawPath,
-    content: Option<String>,
+    #[serde(default, skip_serializing_if = "MatchTarget::is_default")]
+    match_on: MatchTarget,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extensions: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    any_extension: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<RawContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_hex: Option<RawContent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hash_files: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Heuristics::is_empty")]
+    heuristics: Heuristics,
+    #[serde(default, skip_serializing_if = "RawFileCondition::is_empty")]
+    file: RawFileCondition,
+    #[serde(flatten)]
+    flags: RegexFlags,
+    #[serde(default, skip_serializing_if = "RawReadLimit::is_empty")]
+    read: RawReadLimit,
     severity: Severity,
+    #[serde(flatten)]
+    metadata: RuleMetadata,
+    #[serde(default, skip_serializing_if = "RuleExamples::is_empty")]
+    examples: RuleExamples,
 }
 
 impl RawRule {
+    pub fn new(name: String, path: RawPath, severity: Severity) -> Self {
+        Self {
+            name,
+            path,
+            match_on: MatchTarget::default(),
+            extensions: vec![],
+            any_extension: false,
+            content: None,
+            content_hex: None,
+            hashes: vec![],
+            hash_files: vec![],
+            heuristics: Heuristics::default(),
+            file: RawFileCondition::default(),
+            flags: RegexFlags::default(),
+            read: RawReadLimit::default(),
+            severity,
+            metadata: RuleMetadata::default(),
+            examples: RuleExamples::default(),
+        }
+    }
+
+    /// Return the rule matching the path pattern against the given part of the path
+    pub fn with_match_on(mut self, match_on: MatchTarget) -> Self {
+        self.match_on = match_on;
+        self
+    }
+
+    /// Return the rule requiring one of the given file extensions
+    pub fn with_extensions(mut self, extensions: Vec<String>, any_extension: bool) -> Self {
+        self.extensions = extensions;
+        self.any_extension = any_extension;
+        self
+    }
+
+    /// Return the rule with the given content conditions and the flags to compile them with
+    pub fn with_content(
+        mut self,
+        content: Option<RawContent>,
+        content_hex: Option<RawContent>,
+        flags: RegexFlags,
+    ) -> Self {
+        self.content = content;
+        self.content_hex = content_hex;
+        self.flags = flags;
+        self
+    }
+
+    /// Return the rule with the given file hashes and hash list files
+    pub fn with_hashes(mut self, hashes: Vec<String>, hash_files: Vec<PathBuf>) -> Self {
+        self.hashes = hashes;
+        self.hash_files = hash_files;
+        self
+    }
+
+    /// Return the rule with the given thresholds of the content metrics
+    pub fn with_heuristics(mut self, heuristics: Heuristics) -> Self {
+        self.heuristics = heuristics;
+        self
+    }
+
+    /// Return the rule with the given conditions on the file's metadata
+    pub fn with_file_condition(mut self, file: RawFileCondition) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Return the rule with relative hash list file paths resolved against `base_dir`
+    pub fn with_base_dir(mut self, base_dir: &Path) -> Self {
+        self.hash_files = self
+            .hash_files
+            .iter()
+            .map(|hash_file| base_dir.join(hash_file))
+            .collect();
+        self
+    }
+
+    /// Return the rule with the given read limit overrides
+    pub fn with_read_limit(mut self, read: RawReadLimit) -> Self {
+        self.read = read;
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: RuleMetadata, examples: RuleExamples) -> Self {
+        self.metadata = metadata;
+        self.examples = examples;
+        self
+    }
+
     pub fn is_regex_path(&self) -> bool {
         self.path.is_regex()
     }
 
+    pub fn raw_path(&self) -> &RawPath {
+        &self.path
+    }
+
     pub fn path_ref(&self) -> &str {
         self.path.as_str()
     }
@@ -32,11 +158,59 @@ impl RawRule {
         RulePath::String(self.path.as_str().to_owned())
     }
 
-    pub fn content(&self) -> Option<String> {
-        self.content.clone()
+    pub fn match_on(&self) -> MatchTarget {
+        self.match_on
+    }
+
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn any_extension(&self) -> bool {
+        self.any_extension
+    }
+
+    pub fn content(&self) -> Option<&RawContent> {
+        self.content.as_ref()
+    }
+
+    pub fn content_hex(&self) -> Option<&RawContent> {
+        self.content_hex.as_ref()
+    }
+
+    pub fn hashes(&self) -> &[String] {
+        &self.hashes
+    }
+
+    pub fn hash_files(&self) -> &[PathBuf] {
+        &self.hash_files
+    }
+
+    pub fn heuristics(&self) -> Heuristics {
+        self.heuristics
+    }
+
+    pub fn file_condition(&self) -> &RawFileCondition {
+        &self.file
+    }
+
+    pub fn flags(&self) -> RegexFlags {
+        self.flags
+    }
+
+    pub fn read_limit(&self) -> RawReadLimit {
+        self.read
     }
 
     pub fn severity(&self) -> Severity {
         self.severity
     }
+
+    pub fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    pub fn examples(&self) -> &RuleExamples {
+        &self.examples
+    }
 }
@@ -0,0 +1,171 @@
+use crate::errors::*;
+use std::fmt;
+
+/// Default number of bytes read from the beginning of a file
+pub const DEFAULT_READ_HEAD: u64 = 1024 * 1024 * 4;
+
+/// Default number of bytes consecutive chunks overlap in the chunked mode
+pub const DEFAULT_CHUNK_OVERLAP: u64 = 1024 * 4;
+
+/// Defines which parts of a file's content are read to match a Rule's content patterns
+///
+/// By default only the first `head` bytes are read. If `tail` is greater than zero the last
+/// `tail` bytes are read as well, and matched separately from the head. In the `chunked` mode
+/// the whole file is streamed in chunks of `head` bytes, where each chunk starts with the last
+/// `overlap` bytes of the previous one, so that matches spanning two chunks are found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadLimit {
+    /// Number of bytes read from the beginning of the file (the chunk size in the chunked mode)
+    pub head: u64,
+
+    /// Number of bytes read from the end of the file
+    pub tail: u64,
+
+    /// Stream the whole file in chunks
+    pub chunked: bool,
+
+    /// Number of bytes consecutive chunks overlap
+    pub overlap: u64,
+}
+
+impl Default for ReadLimit {
+    fn default() -> Self {
+        ReadLimit {
+            head: DEFAULT_READ_HEAD,
+            tail: 0,
+            chunked: false,
+            overlap: DEFAULT_CHUNK_OVERLAP,
+        }
+    }
+}
+
+impl ReadLimit {
+    /// Return an error if the limit can not be used to read a file
+    pub fn validate(&self) -> Result<()> {
+        if self.head == 0 {
+            bail!("The read limit must be greater than zero");
+        }
+        if self.chunked && self.overlap >= self.head {
+            bail!(
+                "The chunk overlap ({}) must be smaller than the chunk size ({})",
+                format_byte_size(self.overlap),
+                format_byte_size(self.head)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ReadLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.chunked {
+            write!(
+                f,
+                "chunks of {} (overlap {})",
+                format_byte_size(self.head),
+                format_byte_size(self.overlap)
+            )
+        } else if self.tail > 0 {
+            write!(
+                f,
+                "head {}, tail {}",
+                format_byte_size(self.head),
+                format_byte_size(self.tail)
+            )
+        } else {
+            write!(f, "head {}", format_byte_size(self.head))
+        }
+    }
+}
+
+/// Parse a number of bytes with an optional unit suffix (e.g. `512`, `64K`, `4M` or `1G`)
+///
+/// The units are powers of 1024 and case-insensitive. A trailing `B` or `iB` is ignored
+pub fn parse_byte_size(input: &str) -> Result<u64> {
+    let invalid = || -> Error { format!("Invalid byte size '{}'", input).into() };
+    let trimmed = input.trim();
+    let without_suffix = trimmed
+        .strip_suffix("iB")
+        .or_else(|| trimmed.strip_suffix('B'))
+        .or_else(|| trimmed.strip_suffix('b'))
+        .unwrap_or(trimmed);
+    let (number, factor) = match without_suffix.chars().last() {
+        Some('k') | Some('K') => (&without_suffix[..without_suffix.len() - 1], 1024),
+        Some('m') | Some('M') => (&without_suffix[..without_suffix.len() - 1], 1024 * 1024),
+        Some('g') | Some('G') => (
+            &without_suffix[..without_suffix.len() - 1],
+            1024 * 1024 * 1024,
+        ),
+        _ => (without_suffix, 1),
+    };
+
+    number
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| invalid())?
+        .checked_mul(factor)
+        .ok_or_else(invalid)
+}
+
+/// Format the number of bytes using the largest unit it is a multiple of (e.g. `4M`)
+pub fn format_byte_size(bytes: u64) -> String {
+    for (factor, unit) in &[(1024 * 1024 * 1024, "G"), (1024 * 1024, "M"), (1024, "K")] {
+        if bytes > 0 && bytes.is_multiple_of(*factor) {
+            return format!("{}{}", bytes / factor, unit);
+        }
+    }
+
+    format!("{}B", bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_byte_size_test() {
+        assert_eq!(512, parse_byte_size("512").unwrap());
+        assert_eq!(64 * 1024, parse_byte_size("64K").unwrap());
+        assert_eq!(64 * 1024, parse_byte_size("64kB").unwrap());
+        assert_eq!(4 * 1024 * 1024, parse_byte_size("4M").unwrap());
+        assert_eq!(4 * 1024 * 1024, parse_byte_size("4MiB").unwrap());
+        assert_eq!(1024 * 1024 * 1024, parse_byte_size(" 1g ").unwrap());
+        assert!(parse_byte_size("").is_err());
+        assert!(parse_byte_size("M").is_err());
+        assert!(parse_byte_size("-1").is_err());
+        assert!(parse_byte_size("4T").is_err());
+    }
+
+    #[test]
+    fn format_byte_size_test() {
+        assert_eq!("0B", format_byte_size(0));
+        assert_eq!("100B", format_byte_size(100));
+        assert_eq!("4K", format_byte_size(4096));
+        assert_eq!("4M", format_byte_size(DEFAULT_READ_HEAD));
+        assert_eq!("1025K", format_byte_size(1025 * 1024));
+    }
+
+    #[test]
+    fn validate_test() {
+        assert!(ReadLimit::default().validate().is_ok());
+        let zero = ReadLimit {
+            head: 0,
+            ..ReadLimit::default()
+        };
+        assert!(zero.validate().is_err());
+        let overlapping = ReadLimit {
+            head: 1024,
+            overlap: 1024,
+            chunked: true,
+            ..ReadLimit::default()
+        };
+        assert!(overlapping.validate().is_err());
+        // The overlap is only used in the chunked mode
+        let not_chunked = ReadLimit {
+            chunked: false,
+            ..overlapping
+        };
+        assert!(not_chunked.validate().is_ok());
+    }
+}
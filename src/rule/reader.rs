@@ -1,31 +1,259 @@
 use crate::errors::*;
+use crate::rule::configuration::{Configuration, SeverityOverride};
 use crate::rule::pattern_rule::PatternRule;
 use crate::rule::raw_rule::RawRule;
+use serde::Deserialize;
+use serde::{de::IgnoredAny, Deserializer};
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::error::Error as StdError;
+use std::fmt::Display;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::Path;
+#[cfg(feature = "toml")]
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Entry of a rule file
+#[derive(Debug)]
+enum RawEntry {
+    Rule(Box<RawRule>),
+    Include(RawInclude),
+    Disable(RawDisable),
+    Override(RawOverride),
+}
+
+/// `include` directive to load the rules from other files or directories
+///
+/// Relative paths are resolved against the directory of the including file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawInclude {
+    include: OneOrMany,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// `disable` directive to remove builtin rules by name or prefix (e.g. `typo3::*`)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawDisable {
+    disable: OneOrMany,
+}
+
+/// `override` directive to change the severity of builtin rules
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawOverride {
+    #[serde(rename = "override")]
+    overrides: Vec<SeverityOverride>,
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+/// Problem found while reading the rule files
+#[derive(Debug)]
+pub(crate) struct ReaderIssue {
+    /// File that caused the problem
+    pub path: PathBuf,
+    /// The rule that could not be compiled
+    pub raw_rule: Option<RawRule>,
+    pub error: Error,
+}
+
+/// Files that are currently loaded (to detect cycles) or have already been loaded
+#[derive(Default)]
+struct ReaderState {
+    stack: Vec<PathBuf>,
+    loaded: HashSet<PathBuf>,
+    /// If set, problems are collected instead of aborting
+    issues: Option<Vec<ReaderIssue>>,
+}
+
+impl ReaderState {
+    fn report(&mut self, path: &Path, raw_rule: Option<RawRule>, error: Error) -> Result<()> {
+        match self.issues {
+            Some(ref mut issues) => {
+                issues.push(ReaderIssue {
+                    path: path.to_owned(),
+                    raw_rule,
+                    error,
+                });
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+}
 
 pub struct Reader {}
 
 impl Reader {
-    pub fn read_rules_from_file(path: &Path) -> Result<Vec<PatternRule>> {
-        let raw_rules = Reader::read_raw_rules_from_file(path)?;
+    /// Read the rules and the changes of the builtin rules from the given files and directories
+    ///
+    /// Directories are searched (non-recursively) for rule files, which are read in alphabetical
+    /// order. `include` directives are resolved, while files that are included multiple times are
+    /// only read once.
+    pub fn read_configuration_from_paths<P: AsRef<Path>>(paths: &[P]) -> Result<Configuration> {
+        let mut state = ReaderState::default();
+        let mut configuration = Configuration::default();
+        for path in paths {
+            Reader::read_rules_from_path(path.as_ref(), &mut state, &mut configuration)?;
+        }
+
+        Ok(configuration)
+    }
+
+    /// Read the rules and the changes of the builtin rules from the given files and directories
+    ///
+    /// In contrast to [`read_configuration_from_paths`] invalid files and entries are skipped and
+    /// returned as [`ReaderIssue`]s
+    pub(crate) fn read_configuration_leniently<P: AsRef<Path>>(
+        paths: &[P],
+    ) -> (Configuration, Vec<ReaderIssue>) {
+        let mut state = ReaderState {
+            issues: Some(vec![]),
+            ..Default::default()
+        };
+        let mut configuration = Configuration::default();
+        for path in paths {
+            // Problems are collected in the state, so this can not fail
+            let _ = Reader::read_rules_from_path(path.as_ref(), &mut state, &mut configuration);
+        }
+
+        (configuration, state.issues.unwrap_or_default())
+    }
+
+    /// Read the rules from the given files and directories
+    ///
+    /// See [`read_configuration_from_paths`] for details
+    #[cfg(test)]
+    pub fn read_rules_from_paths<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<PatternRule>> {
+        Ok(Reader::read_configuration_from_paths(paths)?.rules)
+    }
+
+    fn read_rules_from_path(
+        path: &Path,
+        state: &mut ReaderState,
+        configuration: &mut Configuration,
+    ) -> Result<()> {
+        if path.is_dir() {
+            let files = match list_rule_files(path) {
+                Ok(files) => files,
+                Err(e) => return state.report(path, None, e),
+            };
+            for file in files {
+                Reader::read_rules_from_file(&file, state, configuration)?;
+            }
+
+            Ok(())
+        } else {
+            Reader::read_rules_from_file(path, state, configuration)
+        }
+    }
+
+    fn read_rules_from_file(
+        path: &Path,
+        state: &mut ReaderState,
+        configuration: &mut Configuration,
+    ) -> Result<()> {
+        let canonical_path = match path.canonicalize() {
+            Ok(p) => p,
+            Err(e) => return state.report(path, None, build_open_error(path, &e).into()),
+        };
+        if state.stack.contains(&canonical_path) {
+            let mut chain: Vec<String> = state
+                .stack
+                .iter()
+                .skip_while(|p| **p != canonical_path)
+                .map(|p| p.display().to_string())
+                .collect();
+            chain.push(canonical_path.display().to_string());
+            let error =
+                ErrorKind::ReaderError(format!("Include cycle detected: {}", chain.join(" -> ")));
+            return state.report(path, None, error.into());
+        }
+        if !state.loaded.insert(canonical_path.clone()) {
+            debug!("Skip already loaded file '{}'", path.display());
+            return Ok(());
+        }
+
+        debug!("Read rules from file '{}'", path.display());
+        let entries = match Reader::read_raw_entries_from_file(path) {
+            Ok(entries) => entries,
+            Err(e) => return state.report(path, None, e),
+        };
+        state.stack.push(canonical_path);
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for (index, entry) in entries.into_iter().enumerate() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    state.report(path, None, e)?;
+                    continue;
+                }
+            };
+            match entry {
+                RawEntry::Rule(raw_rule) => {
+                    match PatternRule::try_from((*raw_rule).clone().with_base_dir(base_dir)) {
+                        Ok(rule) => configuration.rules.push(rule.with_source(path)),
+                        Err(e) => state.report(
+                            path,
+                            Some(*raw_rule),
+                            build_rule_error(path, index, &e).into(),
+                        )?,
+                    }
+                }
+                RawEntry::Include(include) => {
+                    for include_path in include.include.into_vec() {
+                        Reader::read_rules_from_path(
+                            &base_dir.join(include_path),
+                            state,
+                            configuration,
+                        )?;
+                    }
+                }
+                RawEntry::Disable(disable) => configuration
+                    .builtin_changes
+                    .disable
+                    .append(&mut disable.disable.into_vec()),
+                RawEntry::Override(mut severity_override) => configuration
+                    .builtin_changes
+                    .overrides
+                    .append(&mut severity_override.overrides),
+            }
+        }
+        state.stack.pop();
 
-        PatternRule::from_raw_rules(raw_rules)
+        Ok(())
     }
 
-    fn read_raw_rules_from_file(path: &Path) -> Result<Vec<RawRule>> {
+    fn read_raw_entries_from_file(path: &Path) -> Result<Vec<Result<RawEntry>>> {
         match path.extension() {
             None => Err(build_file_type_error(path)),
             Some(os_str) => match os_str.to_str() {
                 None => Err(build_file_type_error(path)),
 
                 #[cfg(feature = "json")]
-                Some("json") => Reader::read_rules_from_json_file(path),
+                Some("json") => Reader::read_entries_from_json_file(path),
 
-                #[cfg(feature = "yaml")]
-                Some("yaml") => Reader::read_rules_from_yaml_file(path),
+                Some("yaml") | Some("yml") => Reader::read_entries_from_yaml_file(path),
+
+                #[cfg(feature = "toml")]
+                Some("toml") => Reader::read_entries_from_toml_file(path),
 
                 Some(t) => bail!("No deserializer for the file type '{}'", t),
             },
@@ -33,25 +261,108 @@ impl Reader {
     }
 
     #[cfg(feature = "json")]
-    fn read_rules_from_json_file(path: &Path) -> Result<Vec<RawRule>> {
+    fn read_entries_from_json_file(path: &Path) -> Result<Vec<Result<RawEntry>>> {
         let file: BufReader<File> = get_file_reader(path)?;
-        match serde_json::from_reader::<BufReader<File>, Vec<RawRule>>(file) {
-            Ok(r) => Ok(r),
+        match serde_json::from_reader::<BufReader<File>, Vec<serde_json::Value>>(file) {
+            Ok(values) => Ok(build_raw_entries(path, values)),
             Err(e) => Err(build_deserialize_error(path, &e)),
         }
     }
 
-    #[cfg(feature = "yaml")]
-    fn read_rules_from_yaml_file(path: &Path) -> Result<Vec<RawRule>> {
+    fn read_entries_from_yaml_file(path: &Path) -> Result<Vec<Result<RawEntry>>> {
         let file: BufReader<File> = get_file_reader(path)?;
-        match serde_yaml::from_reader::<BufReader<File>, Vec<RawRule>>(file) {
-            Ok(r) => Ok(r),
+        match serde_yaml::from_reader::<BufReader<File>, Vec<serde_yaml::Value>>(file) {
+            Ok(values) => Ok(build_raw_entries(path, values)),
+            Err(e) => Err(build_deserialize_error(path, &e)),
+        }
+    }
+
+    /// Read the entries from the `rules` array of tables of a TOML file
+    #[cfg(feature = "toml")]
+    fn read_entries_from_toml_file(path: &Path) -> Result<Vec<Result<RawEntry>>> {
+        let mut content = String::new();
+        if let Err(e) = get_file_reader(path)?.read_to_string(&mut content) {
+            return Err(build_deserialize_error(path, &e));
+        }
+        match toml::from_str::<TomlRuleFile>(&content) {
+            Ok(file) => Ok(build_raw_entries(path, file.rules)),
             Err(e) => Err(build_deserialize_error(path, &e)),
         }
     }
 }
 
-fn build_file_type_error(path: &Path) -> Error {
+/// TOML documents are tables, so the entries are stored in the `rules` array
+#[cfg(feature = "toml")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlRuleFile {
+    #[serde(default)]
+    rules: Vec<toml::Value>,
+}
+
+/// Deserialize each of the `values` into a directive or a rule
+fn build_raw_entries<V>(path: &Path, values: Vec<V>) -> Vec<Result<RawEntry>>
+where
+    V: Clone + for<'de> Deserializer<'de>,
+    for<'de> <V as Deserializer<'de>>::Error: Display,
+{
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            // Detect the kind of the entry by its keys, to report the errors of the right kind
+            let keys =
+                HashMap::<String, IgnoredAny>::deserialize(value.clone()).unwrap_or_default();
+            let entry = if keys.contains_key("include") {
+                RawInclude::deserialize(value).map(RawEntry::Include)
+            } else if keys.contains_key("disable") {
+                RawDisable::deserialize(value).map(RawEntry::Disable)
+            } else if keys.contains_key("override") {
+                RawOverride::deserialize(value).map(RawEntry::Override)
+            } else {
+                RawRule::deserialize(value).map(|rule| RawEntry::Rule(Box::new(rule)))
+            };
+
+            entry.map_err(|e| build_rule_error(path, index, &e).into())
+        })
+        .collect()
+}
+
+/// Return the files in `directory` that have a supported file type, sorted by name
+pub(super) fn list_rule_files(directory: &Path) -> Result<Vec<PathBuf>> {
+    let read_dir = match directory.read_dir() {
+        Ok(r) => r,
+        Err(e) => bail!(build_open_error(directory, &e)),
+    };
+    let mut files = vec![];
+    for entry in read_dir {
+        let path = match entry {
+            Ok(e) => e.path(),
+            Err(e) => bail!(build_open_error(directory, &e)),
+        };
+        if path.is_file() && is_supported_file_type(&path) {
+            files.push(path);
+        } else {
+            debug!("Skip file '{}' in rule directory", path.display());
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+fn is_supported_file_type(path: &Path) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        #[cfg(feature = "json")]
+        Some("json") => true,
+        Some("yaml") | Some("yml") => true,
+        #[cfg(feature = "toml")]
+        Some("toml") => true,
+        _ => false,
+    }
+}
+
+pub(super) fn build_file_type_error(path: &Path) -> Error {
     ErrorKind::ReaderError(format!(
         "Could not detect the file type of '{}'",
         path.display()
@@ -59,7 +370,7 @@ fn build_file_type_error(path: &Path) -> Error {
     .into()
 }
 
-fn build_deserialize_error(path: &Path, error: &dyn StdError) -> Error {
+pub(super) fn build_deserialize_error(path: &Path, error: &dyn StdError) -> Error {
     ErrorKind::ReaderError(format!(
         "Could not deserialize the file '{}': {}",
         path.display(),
@@ -68,21 +379,34 @@ fn build_deserialize_error(path: &Path, error: &dyn StdError) -> Error {
     .into()
 }
 
-fn get_file_reader(path: &Path) -> Result<BufReader<File>, Error> {
+fn build_rule_error(path: &Path, index: usize, error: &dyn Display) -> ErrorKind {
+    ErrorKind::ReaderError(format!(
+        "Invalid rule at index {} in file '{}': {}",
+        index,
+        path.display(),
+        error
+    ))
+}
+
+pub(super) fn build_open_error(path: &Path, error: &dyn Display) -> ErrorKind {
+    ErrorKind::ReaderError(format!(
+        "Could not open file '{}' for reading: {}",
+        path.display(),
+        error
+    ))
+}
+
+pub(super) fn get_file_reader(path: &Path) -> Result<BufReader<File>, Error> {
     match File::open(path) {
         Ok(f) => Ok(BufReader::new(f)),
-        Err(e) => Err(ErrorKind::ReaderError(format!(
-            "Could not open file '{}' for reading: {}",
-            path.display(),
-            e
-        ))
-        .into()),
+        Err(e) => Err(build_open_error(path, &e).into()),
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::rule::{HashAlgorithm, MatchTarget, RawContent, ReadLimit, RuleTrait};
     use crate::severity::Severity;
 
     fn path(rule: &RawRule) -> String {
@@ -90,12 +414,26 @@ mod test {
     }
 
     fn content(rule: &RawRule) -> String {
-        rule.content().unwrap()
+        match rule.content().unwrap() {
+            RawContent::Pattern(pattern) => pattern.clone(),
+            RawContent::Group(_) => panic!("Expected a single content pattern"),
+        }
+    }
+
+    fn rules_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/rules")
     }
 
-    fn test_loaded_rules(result: Result<Vec<RawRule>, Error>) {
+    fn test_loaded_rules(result: Result<Vec<Result<RawEntry>>, Error>) {
         assert!(result.is_ok(), "{}", result.unwrap_err());
-        let rules = result.unwrap();
+        let rules: Vec<RawRule> = result
+            .unwrap()
+            .into_iter()
+            .map(|entry| match entry.unwrap() {
+                RawEntry::Rule(rule) => *rule,
+                _ => panic!("Unexpected directive"),
+            })
+            .collect();
 
         assert_eq!(2, rules.len());
 
@@ -103,16 +441,29 @@ mod test {
         assert_eq!("some/path", path(&rules[0]));
         assert_eq!("some bad content", content(&rules[0]));
         assert_eq!(Severity::CRITICAL, rules[0].severity());
+        let metadata = rules[0].metadata();
+        assert_eq!(Some("HS-TEST-0001"), metadata.id.as_deref());
+        assert_eq!(
+            Some("Some bad content was found"),
+            metadata.description.as_deref()
+        );
+        assert_eq!(
+            vec!["https://example.com/some-rule", "CVE-2019-0001"],
+            metadata.references
+        );
+        assert_eq!(vec!["backdoor", "test"], metadata.tags);
+        assert_eq!(Some("Jane Doe"), metadata.author.as_deref());
 
         assert_eq!("some whitelist rule", rules[1].name());
         assert_eq!("\\.php", path(&rules[1]));
         assert_eq!("love", content(&rules[1]));
         assert_eq!(Severity::WHITELIST, rules[1].severity());
+        assert!(rules[1].metadata().is_empty());
     }
 
     #[test]
     fn read_raw_rules_from_file_invalid() {
-        let result = Reader::read_raw_rules_from_file(Path::new(&format!(
+        let result = Reader::read_raw_entries_from_file(Path::new(&format!(
             "{}/tests",
             env!("CARGO_MANIFEST_DIR")
         )));
@@ -122,7 +473,7 @@ mod test {
             result.unwrap_err().description()
         );
 
-        let result = Reader::read_raw_rules_from_file(Path::new(&format!(
+        let result = Reader::read_raw_entries_from_file(Path::new(&format!(
             "{}/tests.txt",
             env!("CARGO_MANIFEST_DIR")
         )));
@@ -136,7 +487,7 @@ mod test {
     #[test]
     #[cfg(feature = "json")]
     fn read_raw_rules_from_file_with_not_existing_json() {
-        let result = Reader::read_raw_rules_from_file(Path::new(&format!(
+        let result = Reader::read_raw_entries_from_file(Path::new(&format!(
             "{}/tests/resources/rules/not-a-file.json",
             env!("CARGO_MANIFEST_DIR")
         )));
@@ -150,7 +501,7 @@ mod test {
     #[test]
     #[cfg(feature = "json")]
     fn read_raw_rules_from_file_with_json() {
-        let result = Reader::read_raw_rules_from_file(Path::new(&format!(
+        let result = Reader::read_raw_entries_from_file(Path::new(&format!(
             "{}/tests/resources/rules/rules.json",
             env!("CARGO_MANIFEST_DIR")
         )));
@@ -158,9 +509,8 @@ mod test {
     }
 
     #[test]
-    #[cfg(feature = "yaml")]
     fn read_raw_rules_from_file_with_not_existing_yaml() {
-        let result = Reader::read_raw_rules_from_file(Path::new(&format!(
+        let result = Reader::read_raw_entries_from_file(Path::new(&format!(
             "{}/tests/resources/rules/not-a-file.yaml",
             env!("CARGO_MANIFEST_DIR")
         )));
@@ -172,12 +522,220 @@ mod test {
     }
 
     #[test]
-    #[cfg(feature = "yaml")]
     fn read_raw_rules_from_file_with_yaml() {
-        let result = Reader::read_raw_rules_from_file(Path::new(&format!(
+        let result = Reader::read_raw_entries_from_file(Path::new(&format!(
             "{}/tests/resources/rules/rules.yaml",
             env!("CARGO_MANIFEST_DIR")
         )));
         test_loaded_rules(result);
     }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn read_raw_rules_from_file_with_toml() {
+        let result = Reader::read_raw_entries_from_file(Path::new(&format!(
+            "{}/tests/resources/rules/rules.toml",
+            env!("CARGO_MANIFEST_DIR")
+        )));
+        test_loaded_rules(result);
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn read_raw_rules_from_file_with_invalid_toml() {
+        let result = Reader::read_raw_entries_from_file(&rules_dir().join("include/invalid.toml"));
+        let error = result.unwrap_err().to_string();
+
+        assert!(
+            error.contains("Could not deserialize the file"),
+            "{}",
+            error
+        );
+        assert!(error.contains("unknown field `rule`"), "{}", error);
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn read_rules_from_paths_with_includes_and_directory() {
+        let rules = Reader::read_rules_from_paths(&[
+            rules_dir().join("include/main.yaml"),
+            rules_dir().join("rules.d"),
+        ])
+        .unwrap();
+        let names: Vec<&str> = rules.iter().map(|r| r.name()).collect();
+
+        assert_eq!(
+            vec![
+                "main rule",
+                "shared rule",
+                "customer rule",
+                "main rule 2",
+                "a rule",
+                "b rule",
+                "c rule"
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_read_limit() {
+        let rules = Reader::read_rules_from_paths(&[rules_dir().join("read_limit.yaml")]).unwrap();
+
+        let read_limit = rules[0].read_limit();
+        assert_eq!(64 * 1024, read_limit.head);
+        assert_eq!(64 * 1024, read_limit.tail);
+        assert!(!read_limit.chunked);
+
+        let read_limit = rules[1].read_limit();
+        assert_eq!(ReadLimit::default().head, read_limit.head);
+        assert_eq!(1024, read_limit.overlap);
+        assert!(read_limit.chunked);
+
+        // The overrides of the rule take precedence over the default read limit
+        let default_read_limit = ReadLimit {
+            head: 1024 * 1024,
+            tail: 1024,
+            ..ReadLimit::default()
+        };
+        let rule = rules[0]
+            .clone()
+            .with_default_read_limit(default_read_limit)
+            .unwrap();
+        assert_eq!(64 * 1024, rule.read_limit().head);
+        assert_eq!(64 * 1024, rule.read_limit().tail);
+        let rule = rules[1]
+            .clone()
+            .with_default_read_limit(default_read_limit)
+            .unwrap();
+        assert_eq!(1024 * 1024, rule.read_limit().head);
+        assert_eq!(1024, rule.read_limit().tail);
+
+        // The chunk overlap of the rule must be smaller than the chunk size
+        let small_chunks = ReadLimit {
+            head: 512,
+            ..ReadLimit::default()
+        };
+        let error = rules[1]
+            .clone()
+            .with_default_read_limit(small_chunks)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("Read limit of rule 'Payload anywhere in large archives' is invalid"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_match_on() {
+        let rules = Reader::read_rules_from_paths(&[rules_dir().join("match_on.yaml")]).unwrap();
+
+        assert_eq!(MatchTarget::RelativePath, rules[0].match_on());
+        assert_eq!(MatchTarget::FileName, rules[1].match_on());
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_extensions() {
+        let rules = Reader::read_rules_from_paths(&[rules_dir().join("extensions.yaml")]).unwrap();
+
+        assert_eq!(&["php", "phtml", "phar"], rules[0].extensions());
+        assert!(!rules[0].any_extension());
+        assert_eq!(&["php"], rules[1].extensions());
+        assert!(rules[1].any_extension());
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_heuristics() {
+        let rules = Reader::read_rules_from_paths(&[rules_dir().join("heuristics.yaml")]).unwrap();
+        let heuristics = rules[0].heuristics().unwrap();
+
+        assert_eq!(Some(5.2), heuristics.min_entropy);
+        assert_eq!(Some(0.35), heuristics.min_non_alphanumeric_ratio);
+        assert_eq!(Some(1024), heuristics.min_blob_size);
+        assert_eq!(None, heuristics.min_longest_line);
+        assert!(!rules[0].has_content());
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_file_condition() {
+        let rules =
+            Reader::read_rules_from_paths(&[rules_dir().join("file_condition.yaml")]).unwrap();
+        let file_condition = rules[0].file_condition().unwrap();
+
+        assert_eq!(Some(true), file_condition.world_writable);
+        assert_eq!(Some(1024 * 1024), file_condition.max_size);
+        assert_eq!(Some(0), file_condition.owner);
+        assert_eq!(
+            Some(std::time::Duration::from_secs(3 * 24 * 60 * 60)),
+            file_condition.modified_within
+        );
+        assert_eq!(Some("root"), rules[0].raw_file_condition().owner.as_deref());
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_hashes() {
+        let rules = Reader::read_rules_from_paths(&[rules_dir().join("hashes.yaml")]).unwrap();
+
+        assert_eq!(
+            vec![rules_dir().join("hashes/iocs.txt")],
+            rules[0].hash_files()
+        );
+        let hashes = rules[0].hashes().unwrap();
+        assert_eq!(3, hashes.len());
+        assert!(hashes.contains(HashAlgorithm::Md5, "04efcc0fa123c454c510df5082ba9da0"));
+        assert!(hashes.contains(
+            HashAlgorithm::Sha1,
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        ));
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_include_cycle() {
+        let result = Reader::read_rules_from_paths(&[rules_dir().join("include/cycle_a.yaml")]);
+        let error = result.unwrap_err().to_string();
+
+        assert!(error.contains("Include cycle detected"), "{}", error);
+        assert!(
+            error.contains("cycle_a.yaml -> ") && error.ends_with("cycle_a.yaml'"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_invalid_rule() {
+        let result = Reader::read_rules_from_paths(&[rules_dir().join("include/invalid.yaml")]);
+        let error = result.unwrap_err().to_string();
+
+        assert!(
+            error.contains("Invalid rule at index 1 in file"),
+            "{}",
+            error
+        );
+        assert!(error.contains("include/invalid.yaml"), "{}", error);
+        assert!(error.contains("severity"), "{}", error);
+
+        let result =
+            Reader::read_rules_from_paths(&[rules_dir().join("include/invalid_regex.yaml")]);
+        let error = result.unwrap_err().to_string();
+
+        assert!(
+            error.contains("Invalid rule at index 0 in file"),
+            "{}",
+            error
+        );
+        assert!(error.contains("include/invalid_regex.yaml"), "{}", error);
+    }
+
+    #[test]
+    fn read_rules_from_paths_with_not_existing_include() {
+        let result = Reader::read_rules_from_paths(&[rules_dir().join("not-a-file.yaml")]);
+        assert!(result.is_err());
+        assert_eq!(
+            "Invalid configuration file",
+            result.unwrap_err().description()
+        );
+    }
 }
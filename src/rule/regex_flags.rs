@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Flags used to compile the content patterns of a Rule
+///
+/// By default patterns are case-insensitive, `^`/`$` only match at the beginning and end of the
+/// file and `.` does not match new lines. The path pattern is not affected, its casing is
+/// configured on the path itself
+#[derive(Debug, Clone, Copy, Default, PartialOrd, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RegexFlags {
+    /// Match letters only with their exact casing
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub case_sensitive: bool,
+
+    /// Let `^` and `$` match at the beginning and end of each line
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub multi_line: bool,
+
+    /// Let `.` also match `\n`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dot_all: bool,
+}
+
+impl RegexFlags {
+    pub fn new(case_sensitive: bool, multi_line: bool, dot_all: bool) -> Self {
+        Self {
+            case_sensitive,
+            multi_line,
+            dot_all,
+        }
+    }
+
+    /// Return the inline flags to prepend to a pattern (e.g. `(?ims)`)
+    pub(crate) fn to_inline_flags(self) -> String {
+        let flags: String = [
+            (!self.case_sensitive, 'i'),
+            (self.multi_line, 'm'),
+            (self.dot_all, 's'),
+        ]
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, flag)| *flag)
+        .collect();
+
+        if flags.is_empty() {
+            flags
+        } else {
+            format!("(?{})", flags)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn to_inline_flags_test() {
+        assert_eq!("(?i)", RegexFlags::default().to_inline_flags());
+        assert_eq!("", RegexFlags::new(true, false, false).to_inline_flags());
+        assert_eq!("(?ms)", RegexFlags::new(true, true, true).to_inline_flags());
+        assert_eq!(
+            "(?is)",
+            RegexFlags::new(false, false, true).to_inline_flags()
+        );
+    }
+}
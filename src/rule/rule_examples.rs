@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+/// Sample files that a Rule must or must not match
+///
+/// The examples are checked with `hackscanner rules test`
+#[derive(Debug, Clone, Default, PartialOrd, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleExamples {
+    /// Samples the Rule has to match
+    #[serde(default, rename = "match", skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<RuleExample>,
+
+    /// Samples the Rule must not match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub no_match: Vec<RuleExample>,
+}
+
+impl RuleExamples {
+    pub fn new(matches: Vec<RuleExample>, no_match: Vec<RuleExample>) -> Self {
+        Self { matches, no_match }
+    }
+
+    /// Return if no examples are defined
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty() && self.no_match.is_empty()
+    }
+}
+
+/// Sample file path with an optional content snippet
+///
+/// The file does not have to exist. If no `content` is given, the sample is treated as an empty
+/// file
+#[derive(Debug, Clone, PartialOrd, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleExample {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+impl RuleExample {
+    pub fn new<S: Into<String>>(path: S, content: Option<String>) -> Self {
+        Self {
+            path: path.into(),
+            content,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deserialize_test() {
+        let examples: RuleExamples = serde_yaml::from_str(
+            "
+match:
+  - path: uploads/shell.php
+    content: <?php eval($_POST['x']);
+no_match:
+  - path: uploads/image.jpg
+",
+        )
+        .unwrap();
+        assert_eq!(
+            RuleExamples::new(
+                vec![RuleExample::new(
+                    "uploads/shell.php",
+                    Some("<?php eval($_POST['x']);".to_owned())
+                )],
+                vec![RuleExample::new("uploads/image.jpg", None)],
+            ),
+            examples
+        );
+        assert!(serde_yaml::from_str::<RuleExamples>("matches: []").is_err());
+    }
+}
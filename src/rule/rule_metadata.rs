@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Optional descriptive information about a Rule
+#[derive(Debug, Clone, Default, PartialOrd, PartialEq, Deserialize, Serialize)]
+pub struct RuleMetadata {
+    /// Stable identifier of the Rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Explanation why the Rule exists and what to do if it is violated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// URLs or CVE IDs with further information
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<String>,
+
+    /// Tags to group the Rule
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Author of the Rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+impl RuleMetadata {
+    /// Create metadata only containing a description
+    pub fn with_description<S: Into<String>>(description: S) -> Self {
+        RuleMetadata {
+            description: Some(description.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Return if no metadata is defined
+    pub fn is_empty(&self) -> bool {
+        self == &RuleMetadata::default()
+    }
+}
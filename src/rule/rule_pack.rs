@@ -0,0 +1,169 @@
+use crate::errors::*;
+use crate::rule::pattern_rule::PatternRule;
+use crate::rule::raw_rule::RawRule;
+use crate::rule::reader::{build_deserialize_error, build_file_type_error, get_file_reader};
+use serde::Deserialize;
+use std::convert::TryFrom;
+#[cfg(feature = "toml")]
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// "raw" Rule pack
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRulePack {
+    name: String,
+    version: u64,
+    rules: Vec<RawRule>,
+}
+
+/// Named and versioned collection of builtin rules
+///
+/// The builtin rules are shipped as packs embedded in the binary. A pack file with the same name
+/// and a higher version replaces the embedded pack
+#[derive(Debug, Clone)]
+pub struct RulePack {
+    name: String,
+    version: u64,
+    rules: Vec<PatternRule>,
+    source: Option<PathBuf>,
+}
+
+impl RulePack {
+    /// Parse a pack embedded in the binary
+    pub(crate) fn from_embedded_yaml(content: &str) -> Result<Self> {
+        let raw: RawRulePack = match serde_yaml::from_str(content) {
+            Ok(raw) => raw,
+            Err(e) => bail!(ErrorKind::ReaderError(format!(
+                "Could not deserialize the rule pack: {}",
+                e
+            ))),
+        };
+
+        RulePack::from_raw(raw, Path::new(""), None)
+    }
+
+    /// Read the pack from a YAML, JSON or TOML file
+    ///
+    /// Relative `hash_files` of the rules are resolved against the directory of the file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let raw = read_raw_rule_pack(path)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        RulePack::from_raw(raw, base_dir, Some(path.to_owned()))
+    }
+
+    fn from_raw(raw: RawRulePack, base_dir: &Path, source: Option<PathBuf>) -> Result<Self> {
+        let mut rules = Vec::with_capacity(raw.rules.len());
+        for raw_rule in raw.rules {
+            let rule_name = raw_rule.name().to_owned();
+            match PatternRule::try_from(raw_rule.with_base_dir(base_dir)) {
+                Ok(rule) => rules.push(rule),
+                Err(e) => bail!(ErrorKind::ReaderError(format!(
+                    "Invalid rule '{}' in rule pack '{}': {}",
+                    rule_name, raw.name, e
+                ))),
+            }
+        }
+
+        Ok(RulePack {
+            name: raw.name,
+            version: raw.version,
+            rules,
+            source,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn rules(&self) -> &[PatternRule] {
+        &self.rules
+    }
+
+    /// Return the file the pack was read from (`None` for embedded packs)
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    pub fn into_rules(self) -> Vec<PatternRule> {
+        self.rules
+    }
+}
+
+fn read_raw_rule_pack(path: &Path) -> Result<RawRulePack> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml") | Some("yml") => serde_yaml::from_reader(get_file_reader(path)?)
+            .map_err(|e| build_deserialize_error(path, &e)),
+
+        #[cfg(feature = "json")]
+        Some("json") => serde_json::from_reader(get_file_reader(path)?)
+            .map_err(|e| build_deserialize_error(path, &e)),
+
+        #[cfg(feature = "toml")]
+        Some("toml") => {
+            let mut content = String::new();
+            if let Err(e) = get_file_reader(path)?.read_to_string(&mut content) {
+                return Err(build_deserialize_error(path, &e));
+            }
+            toml::from_str(&content).map_err(|e| build_deserialize_error(path, &e))
+        }
+
+        _ => Err(build_file_type_error(path)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rule::RuleTrait;
+
+    fn packs_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/rules/packs")
+    }
+
+    #[test]
+    fn from_embedded_yaml_test() {
+        let pack = RulePack::from_embedded_yaml(
+            "name: test\nversion: 3\nrules:\n- name: eval\n  path: .php\n  content: eval\n  severity: MAJOR\n",
+        )
+        .unwrap();
+        assert_eq!("test", pack.name());
+        assert_eq!(3, pack.version());
+        assert_eq!(None, pack.source());
+        assert_eq!("eval", pack.rules()[0].name());
+
+        assert!(RulePack::from_embedded_yaml("name: test\nrules: []\n").is_err());
+        let invalid_rule = RulePack::from_embedded_yaml(
+            "name: test\nversion: 1\nrules:\n- name: broken\n  path: .php\n  content: '('\n  severity: MAJOR\n",
+        );
+        assert!(invalid_rule
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid rule 'broken' in rule pack 'test'"));
+    }
+
+    #[test]
+    fn from_file_test() {
+        let path = packs_dir().join("php.yaml");
+        let pack = RulePack::from_file(&path).unwrap();
+        assert_eq!("php", pack.name());
+        assert_eq!(2, pack.version());
+        assert_eq!(Some(path.as_path()), pack.source());
+        assert_eq!("php::content::eval", pack.rules()[0].name());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn from_json_file_test() {
+        let pack = RulePack::from_file(&packs_dir().join("extra.json")).unwrap();
+        assert_eq!("extra", pack.name());
+        assert_eq!(1, pack.version());
+        assert_eq!(1, pack.rules().len());
+    }
+}
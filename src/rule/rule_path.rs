@@ -1,3 +1,4 @@
+use globset::GlobMatcher;
 use regex::Regex;
 use std::fmt::{Display, Formatter, Result};
 
@@ -5,6 +6,7 @@ use std::fmt::{Display, Formatter, Result};
 pub enum RulePath {
     Regex(Regex),
     String(String),
+    Glob(GlobMatcher),
 }
 
 impl RulePath {
@@ -16,7 +18,19 @@ impl RulePath {
     pub fn regex(&self) -> &Regex {
         match self {
             Self::Regex(r) => r,
-            Self::String(_) => panic!("Not a Regex Rule Path"),
+            _ => panic!("Not a Regex Rule Path"),
+        }
+    }
+
+    /// Return the path as glob matcher
+    ///
+    /// # Panics
+    ///
+    /// Panics if this variant is not a `RulePath::Glob`
+    pub fn glob(&self) -> &GlobMatcher {
+        match self {
+            Self::Glob(g) => g,
+            _ => panic!("Not a Glob Rule Path"),
         }
     }
 
@@ -28,7 +42,7 @@ impl RulePath {
     pub fn path_str(&self) -> &str {
         match self {
             Self::String(s) => s,
-            Self::Regex(_) => panic!("Not a String Rule Path"),
+            _ => panic!("Not a String Rule Path"),
         }
     }
 }
@@ -41,6 +55,7 @@ impl Display for RulePath {
             match self {
                 RulePath::Regex(regex) => regex.to_string(),
                 RulePath::String(string) => string.to_string(),
+                RulePath::Glob(glob) => glob.glob().to_string(),
             }
         )
     }
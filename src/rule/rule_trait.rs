@@ -1,4 +1,4 @@
-use crate::rule::RulePath;
+use crate::rule::{MatchTarget, RulePath};
 use crate::Severity;
 
 /// Generic trait for Rule functions
@@ -9,6 +9,23 @@ pub trait RuleTrait<T> {
     /// Return the path(-pattern)
     fn path(&self) -> RulePath;
 
+    /// Return the part of the entry's path the path(-pattern) is matched against
+    fn match_on(&self) -> MatchTarget {
+        MatchTarget::Path
+    }
+
+    /// Return the lowercase file extensions (without leading dot) of which one is required
+    ///
+    /// An empty list means that the extension is not checked
+    fn extensions(&self) -> &[String] {
+        &[]
+    }
+
+    /// Return if any extension of the file name is checked (instead of only the last one)
+    fn any_extension(&self) -> bool {
+        false
+    }
+
     /// Return the content to check against
     fn content(&self) -> Option<T>;
 
@@ -24,4 +41,29 @@ pub trait RuleTrait<T> {
     fn is_regex_path(&self) -> bool {
         matches!(self.path(), RulePath::Regex(_))
     }
+
+    /// Return the stable identifier (defaults to the name)
+    fn id(&self) -> &str {
+        self.name()
+    }
+
+    /// Return the description why the Rule exists
+    fn description(&self) -> Option<&str> {
+        None
+    }
+
+    /// Return the URLs or CVE IDs with further information
+    fn references(&self) -> &[String] {
+        &[]
+    }
+
+    /// Return the tags
+    fn tags(&self) -> &[String] {
+        &[]
+    }
+
+    /// Return the author
+    fn author(&self) -> Option<&str> {
+        None
+    }
 }
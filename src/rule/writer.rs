@@ -0,0 +1,122 @@
+use crate::errors::*;
+use crate::rule::pattern_rule::PatternRule;
+use crate::rule::raw_rule::RawRule;
+use std::str::FromStr;
+
+/// File format of exported rules
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ExportFormat {
+    #[cfg(feature = "json")]
+    Json,
+    Yaml,
+}
+
+impl ExportFormat {
+    /// Return the names of all available formats
+    pub fn names() -> &'static [&'static str] {
+        &[
+            "yaml",
+            #[cfg(feature = "json")]
+            "json",
+        ]
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            #[cfg(feature = "json")]
+            "json" => Ok(ExportFormat::Json),
+            "yaml" | "yml" => Ok(ExportFormat::Yaml),
+            _ => bail!("No export format '{}'", s),
+        }
+    }
+}
+
+/// Serialize the `rules` into the rule file format
+///
+/// The result can be loaded with `--configuration` (in combination with `--no-builtin`, if the
+/// builtin rules have been exported too)
+pub fn export_rules(rules: &[PatternRule], format: ExportFormat) -> Result<String> {
+    let raw_rules: Vec<RawRule> = rules.iter().map(PatternRule::to_raw_rule).collect();
+
+    match format {
+        #[cfg(feature = "json")]
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(&raw_rules)?),
+        ExportFormat::Yaml => Ok(serde_yaml::to_string(&raw_rules)?),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rule::get_builtin_rules;
+    use crate::rule::reader::Reader;
+    use crate::test_helpers::TempDir;
+
+    fn round_trip(format: ExportFormat, extension: &str) {
+        let rules = get_builtin_rules();
+        let exported = export_rules(&rules, format).unwrap();
+        let dir = TempDir::new("export-test");
+        let path = dir.file(&format!("rules.{}", extension), exported);
+        let result = Reader::read_rules_from_paths(&[&path]);
+
+        assert_eq!(rules, result.unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn export_rules_json_test() {
+        round_trip(ExportFormat::Json, "json");
+    }
+
+    #[test]
+    fn export_rules_yaml_test() {
+        round_trip(ExportFormat::Yaml, "yaml");
+    }
+
+    #[test]
+    fn export_rules_yaml_format_test() {
+        let rules = vec![
+            PatternRule::with_path(
+                "glob",
+                crate::Severity::MINOR,
+                crate::RawPath::with_glob("uploads/**/*.php"),
+            )
+            .unwrap(),
+            PatternRule::with_path_and_content(
+                "group",
+                crate::Severity::MAJOR,
+                crate::RawPath::with_regex(r"\.php$"),
+                crate::RawContentGroup::any(vec!["a".into(), "b".into()]),
+            )
+            .unwrap()
+            .with_flags(crate::RegexFlags::new(true, false, true))
+            .unwrap()
+            .with_description("Some description"),
+        ];
+
+        assert_eq!(
+            r#"- name: glob
+  path:
+    glob: uploads/**/*.php
+  severity: MINOR
+- name: group
+  path:
+    pattern: \.php$
+    is_regex: true
+  content:
+    any:
+    - a
+    - b
+  case_sensitive: true
+  dot_all: true
+  severity: MAJOR
+  description: Some description
+"#,
+            export_rules(&rules, ExportFormat::Yaml).unwrap()
+        );
+    }
+}
@@ -1,10 +1,10 @@
 use crate::errors::Error;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Display;
 use std::str::FromStr;
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Deserialize, Serialize)]
 pub enum Severity {
     CRITICAL = 90,
     MAJOR = 60,
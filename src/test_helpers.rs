@@ -0,0 +1,65 @@
+//! Fixtures shared by the unit tests
+use crate::StandaloneDirEntry;
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory in the system's temporary directory that is removed with its content when dropped
+///
+/// The directory is also removed if the test panics
+pub(crate) struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create the directory `hackscanner-<name>-<process ID>`
+    pub(crate) fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("hackscanner-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+
+        TempDir { path }
+    }
+
+    /// Write a file with the given `content` into the directory and return its path
+    pub(crate) fn file<C: AsRef<[u8]>>(&self, file_name: &str, content: C) -> PathBuf {
+        let path = self.path.join(file_name);
+        fs::write(&path, content).unwrap();
+
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Return the entry of the `file` in `tests/resources/files/`
+pub(crate) fn get_test_dir_entry(file: &str) -> StandaloneDirEntry {
+    StandaloneDirEntry::from_path(format!(
+        "{}{}{}",
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/resources/files/",
+        file
+    ))
+    .unwrap()
+}
+
+/// Return the entries of a selection of files in `tests/resources/files/` with and without
+/// violations of the builtin rules
+pub(crate) fn get_test_dir_entries() -> Vec<StandaloneDirEntry> {
+    [
+        "dezmond.php",
+        "eval-in-file.php",
+        "multiple_violations.php",
+        "something.tx_mocfilemanager.php",
+        "tx_mocfilemanager.php",
+        "whitelist_me.php",
+        "typo3/typo3conf/bad.php",
+        "typo3/uploads/some_ext/bad_file.php",
+    ]
+    .iter()
+    .map(|file| get_test_dir_entry(file))
+    .collect()
+}
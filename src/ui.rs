@@ -1,4 +1,5 @@
 use ansi_term::Colour;
+use hackscanner_lib::classifier::ExampleResult;
 use hackscanner_lib::*;
 
 pub fn print_summary(min_severity: Severity, summary: &Summary) {
@@ -18,19 +19,204 @@ pub fn print_summary(min_severity: Severity, summary: &Summary) {
     } else {
         print_summary_simple(summary);
     }
+    if summary.partially_scanned() > 0 {
+        println!(
+            "{} file(s) were only partially scanned (see --read-tail and --chunked)",
+            summary.partially_scanned()
+        );
+    }
 
     println!()
 }
 
-pub fn print_ratings(min_severity: Severity, ratings: &[Rating<'_>]) {
+/// Print the `rules` as table
+pub fn print_rules(rules: &[Rule]) {
+    let header = ["NAME", "SEVERITY", "KIND", "PATH", "CONTENT", "SOURCE"];
+    let rows: Vec<[String; 6]> = rules
+        .iter()
+        .map(|rule| {
+            [
+                rule.name().to_owned(),
+                rule.severity().to_string(),
+                format!("{:?}", rule.path_kind()).to_lowercase(),
+                describe_path(rule),
+                describe_content(rule),
+                rule.source()
+                    .map_or_else(|| "builtin".to_owned(), |s| s.display().to_string()),
+            ]
+        })
+        .collect();
+
+    print_table(&header, &rows);
+}
+
+/// Print the builtin rule `packs` as table
+pub fn print_rule_packs(packs: &[RulePack]) {
+    let header = ["NAME", "VERSION", "RULES", "SOURCE"];
+    let rows: Vec<[String; 4]> = packs
+        .iter()
+        .map(|pack| {
+            [
+                pack.name().to_owned(),
+                pack.version().to_string(),
+                pack.rules().len().to_string(),
+                pack.source()
+                    .map_or_else(|| "embedded".to_owned(), |s| s.display().to_string()),
+            ]
+        })
+        .collect();
+
+    print_table(&header, &rows);
+}
+
+fn print_table<const N: usize>(header: &[&str; N], rows: &[[String; N]]) {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+    print_row(header.to_vec());
+    for row in rows {
+        print_row(row.iter().map(String::as_str).collect());
+    }
+}
+
+/// Return the path pattern of the `rule` with its match target and extensions
+fn describe_path(rule: &Rule) -> String {
+    let path = if rule.match_on().is_default() {
+        rule.raw_path().to_owned()
+    } else {
+        format!("{} ({})", rule.raw_path(), rule.match_on())
+    };
+    let extensions = match (rule.extensions(), rule.any_extension()) {
+        ([], _) => return path,
+        (extensions, false) => format!("ext({})", extensions.join(",")),
+        (extensions, true) => format!("any-ext({})", extensions.join(",")),
+    };
+    if path.is_empty() {
+        extensions
+    } else {
+        format!("{} {}", path, extensions)
+    }
+}
+
+/// Return the text and hex encoded content patterns of the `rule`
+fn describe_content(rule: &Rule) -> String {
+    let content = match (rule.raw_content(), rule.raw_content_hex()) {
+        (Some(content), Some(hex)) => format!("{} hex({})", content, hex),
+        (Some(content), None) => content.to_string(),
+        (None, Some(hex)) => format!("hex({})", hex),
+        (None, None) => "-".to_owned(),
+    };
+    let content = match rule.hashes() {
+        Some(hashes) if content == "-" => format!("{} hash(es)", hashes.len()),
+        Some(hashes) => format!("{} {} hash(es)", content, hashes.len()),
+        None => content,
+    };
+    let content = match rule.heuristics() {
+        Some(heuristics) if content == "-" => format!("heuristics({})", heuristics),
+        Some(heuristics) => format!("{} heuristics({})", content, heuristics),
+        None => content,
+    };
+    match rule.file_condition() {
+        Some(file_condition) if content == "-" => format!("file({})", file_condition),
+        Some(file_condition) => format!("{} file({})", content, file_condition),
+        None => content,
+    }
+}
+
+/// Print the failed (and if `verbose` the passed) examples followed by the number of failures
+pub fn print_example_results(results: &[ExampleResult<'_>], verbose: bool) {
+    if results.is_empty() {
+        println!("No rule defines examples");
+        return;
+    }
+    for result in results {
+        if result.passed() && !verbose {
+            continue;
+        }
+        let content = match result.example.content {
+            Some(ref content) => format!(" with content '{}'", content),
+            None => String::new(),
+        };
+        println!(
+            "{} rule '{}' ({}): expected {} for path '{}'{}",
+            if result.passed() { "PASS" } else { "FAIL" },
+            result.rule.name(),
+            result
+                .rule
+                .source()
+                .map_or_else(|| "builtin".to_owned(), |s| s.display().to_string()),
+            if result.expect_match {
+                "match"
+            } else {
+                "no match"
+            },
+            result.example.path,
+            content
+        );
+    }
+    let failures = results.iter().filter(|r| !r.passed()).count();
+    println!("{} example(s), {} failure(s)", results.len(), failures);
+}
+
+/// Print the problems found in the rules followed by the number of errors and warnings
+pub fn print_lint_issues(issues: &[LintIssue]) {
+    for issue in issues {
+        println!("{}", issue);
+    }
+    let errors = issues
+        .iter()
+        .filter(|i| i.level == LintLevel::Error)
+        .count();
+    println!("{} error(s), {} warning(s)", errors, issues.len() - errors);
+}
+
+pub fn print_ratings(min_severity: Severity, ratings: &[Rating<'_>], verbose: bool) {
     for rating in ratings {
         if rating.rating() >= min_severity as isize {
-            print_rating(rating);
+            print_rating(rating, verbose);
         }
     }
 }
 
-pub fn print_rating(rating: &Rating<'_>) {
+#[cfg(feature = "json")]
+pub fn print_json(
+    min_severity: Severity,
+    summary: &Summary,
+    ratings: &[Rating<'_>],
+) -> Result<(), Error> {
+    println!("{}", report::to_json(min_severity, summary, ratings)?);
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+pub fn print_sarif(
+    min_severity: Severity,
+    rules: &[Rule],
+    ratings: &[Rating<'_>],
+) -> Result<(), Error> {
+    println!("{}", report::to_sarif(min_severity, rules, ratings)?);
+
+    Ok(())
+}
+
+pub fn print_junit(min_severity: Severity, ratings: &[Rating<'_>]) {
+    print!("{}", report::to_junit(min_severity, ratings));
+}
+
+pub fn print_rating(rating: &Rating<'_>, verbose: bool) {
     let supports_color = match term::stdout() {
         Some(t) => t.supports_color(),
         None => false,
@@ -40,6 +226,10 @@ pub fn print_rating(rating: &Rating<'_>) {
     } else {
         print_rating_simple(rating)
     }
+    print_matches(rating);
+    if verbose {
+        print_rule_metadata(rating);
+    }
 }
 
 pub fn print_validation(rating: &Rating<'_>, verbose: bool) {
@@ -52,6 +242,55 @@ pub fn print_validation(rating: &Rating<'_>, verbose: bool) {
     } else {
         print_validation_simple(rating, verbose)
     }
+    print_matches(rating);
+    if verbose {
+        print_rule_metadata(rating);
+    }
+}
+
+/// Print the content matches of all violations indented below the rating
+fn print_matches(rating: &Rating<'_>) {
+    for violation in rating.violations() {
+        for content_match in violation.matches() {
+            println!("{:11}{}: {}", "", violation.name(), content_match);
+        }
+        if let Some(metrics) = violation.metrics() {
+            println!("{:11}{}: {}", "", violation.name(), metrics);
+        }
+    }
+    if let Some(partial_scan) = rating.partial_scan() {
+        println!("{:11}Partially scanned: {}", "", partial_scan);
+    }
+}
+
+/// Print the metadata of the violated rules indented below the rating
+fn print_rule_metadata(rating: &Rating<'_>) {
+    for violation in rating.violations() {
+        let rule = match violation.rule() {
+            Some(rule) => rule,
+            None => continue,
+        };
+        if rule.metadata().is_empty() {
+            continue;
+        }
+        if rule.id() == rule.name() {
+            println!("{:11}{}", "", rule.name());
+        } else {
+            println!("{:11}{} [{}]", "", rule.name(), rule.id());
+        }
+        if let Some(description) = rule.description() {
+            println!("{:13}{}", "", description);
+        }
+        if !rule.tags().is_empty() {
+            println!("{:13}Tags: {}", "", rule.tags().join(", "));
+        }
+        for reference in rule.references() {
+            println!("{:13}See: {}", "", reference);
+        }
+        if let Some(author) = rule.author() {
+            println!("{:13}Author: {}", "", author);
+        }
+    }
 }
 
 fn get_path_as_string(rating: &Rating<'_>) -> String {
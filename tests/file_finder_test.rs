@@ -21,4 +21,116 @@ mod file_finder_test_suite {
 
         assert_single_path(matches);
     }
+
+    #[test]
+    fn find_files_iter_test() {
+        let rules = get_rules_multiple_results();
+        let matches: Vec<WalkdirDirEntry> =
+            file_finder::find_files_iter(get_test_dir(), &rules).collect();
+        assert_multiple_paths(matches);
+
+        let rules = get_rules_single_result();
+        let matches: Vec<WalkdirDirEntry> =
+            file_finder::find_files_iter(get_test_dir(), &rules).collect();
+        assert_single_path(matches);
+    }
+
+    #[test]
+    fn find_files_match_on_test() {
+        let rules = vec![
+            Rule::with_path("name", Severity::NOTICE, RawPath::with_glob("dezmond.*"))
+                .unwrap()
+                .with_match_on(MatchTarget::FileName),
+            Rule::with_path(
+                "relative",
+                Severity::NOTICE,
+                RawPath::with_regex("^files/bad"),
+            )
+            .unwrap()
+            .with_match_on(MatchTarget::RelativePath),
+            Rule::with_path("dir", Severity::NOTICE, RawPath::with_regex("/somephp$"))
+                .unwrap()
+                .with_match_on(MatchTarget::Dir),
+        ];
+        let root = format!("{}/resources", get_test_dir());
+        let mut matches: Vec<String> = file_finder::find_files(root, &rules)
+            .iter()
+            .map(|m| m.relative_path().to_string_lossy().into_owned())
+            .collect();
+        matches.sort();
+
+        assert_eq!(
+            vec![
+                "files/bad-words.txt",
+                "files/dezmond.php",
+                "files/somephp/file.php"
+            ],
+            matches
+        );
+    }
+
+    #[test]
+    fn find_files_relative_path_independent_of_root_test() {
+        let rules = vec![Rule::with_path(
+            "relative",
+            Severity::NOTICE,
+            RawPath::with_regex("^resources/files/dezmond"),
+        )
+        .unwrap()
+        .with_match_on(MatchTarget::RelativePath)];
+
+        let absolute = file_finder::find_files(get_test_dir(), &rules);
+        let relative = file_finder::find_files("tests", &rules);
+
+        assert_eq!(1, absolute.len());
+        assert_eq!(1, relative.len());
+        assert_eq!(absolute[0].relative_path(), relative[0].relative_path());
+    }
+
+    #[test]
+    fn find_files_with_known_good_hash_test() {
+        let rules = vec![
+            Rule::with_path("php", Severity::MAJOR, RawPath::with_glob("files/*.php")).unwrap(),
+            Rule::with_path("known-good", Severity::WHITELIST, RawPath::with_path(""))
+                .unwrap()
+                .with_hashes(&["cdd8f6ccca624908eb49cb34124855597326cecc099532342f6b8968d0d1a962"])
+                .unwrap(),
+        ];
+        let matches = file_finder::find_files(get_test_dir(), &rules);
+
+        // The allowlist neither selects files nor hashes them, the classifier checks the hashes
+        assert!(!matches.is_empty());
+        assert!(
+            matches
+                .iter()
+                .all(|m| m.path().to_string_lossy().ends_with(".php")),
+            "{:?}",
+            matches
+        );
+        assert!(matches.iter().any(|m| m.path().ends_with("dezmond.php")));
+    }
+
+    #[test]
+    fn find_files_with_glob_test() {
+        let rules = vec![
+            Rule::with_path(
+                "uploads",
+                Severity::MAJOR,
+                RawPath::with_glob("uploads/**/*.php"),
+            )
+            .unwrap(),
+            Rule::with_path(
+                "extension-builder-backups",
+                Severity::WHITELIST,
+                RawPath::with_glob("**/tx_extensionbuilder/backups/*.php"),
+            )
+            .unwrap(),
+        ];
+        let matches = file_finder::find_files(get_test_dir(), &rules);
+
+        assert_eq!(1, matches.len(), "{:?}", matches);
+        assert!(matches[0]
+            .path()
+            .ends_with("typo3/uploads/some_ext/bad_file.php"));
+    }
 }
@@ -23,6 +23,13 @@ mod file_finder_test_suite_ftw {
         assert_single_path(matches);
     }
 
+    #[test]
+    fn find_files_relative_path_test() {
+        let rules = get_rules_multiple_results();
+        let matches = FileFinder::new().find(get_test_dir(), &rules);
+        assert_relative_paths(matches);
+    }
+
     #[test]
     fn find_files_one_multi_threading_test() {
         test_multi_threading(FileFinder::new());
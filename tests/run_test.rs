@@ -16,9 +16,7 @@ fn assert_contains_entry_with_score(
         if path_as_string.contains(path) {
             matching_rating = Some(rating);
 
-            if rating.rating() == score {
-                return;
-            } else if !equals_score && rating.rating() >= score {
+            if rating.rating() == score || (!equals_score && rating.rating() >= score) {
                 return;
             }
         }
@@ -44,14 +42,12 @@ fn assert_not_contains_entry(ratings: &[Rating<'_>], path: &str) {
 fn assert_not_contains_entry_with_score(ratings: &[Rating<'_>], score: isize, path: &str) {
     for rating in ratings {
         let path_as_string = rating.entry().path().to_string_lossy().into_owned();
-        if path_as_string.contains(path) {
-            if rating.rating() >= score {
-                panic!(
-                    "Must not find entry {:?} with rating {}",
-                    rating.entry().path(),
-                    rating.rating()
-                );
-            }
+        if path_as_string.contains(path) && rating.rating() >= score {
+            panic!(
+                "Must not find entry {:?} with rating {}",
+                rating.entry().path(),
+                rating.rating()
+            );
         }
     }
 }
@@ -215,3 +211,134 @@ fn run_builtin_rules_test() {
         "tests/resources/files/typo3/uploads/tx_ext_with_php_in_name/index.html",
     );
 }
+
+#[test]
+fn run_rules_with_content_group_test() {
+    configure_logging(simplelog::LevelFilter::Error);
+    let rules = vec![Rule::with_path_and_content(
+        "combined",
+        Severity::CRITICAL,
+        RawPath::with_path(".php"),
+        RawContentGroup::all(vec![r"error_reporting\(".into(), r"\bsystem\(".into()])
+            .with_none(vec![r"\beval\(".into()]),
+    )
+    .unwrap()];
+
+    let files = file_finder::find_files(format!("{}/tests", env!("CARGO_MANIFEST_DIR")), &rules);
+    let ratings = rate_entries(&files, &rules);
+
+    assert_contains_entry_with_score(
+        &ratings,
+        Severity::CRITICAL as isize,
+        "/tests/resources/files/multiple_violations.php",
+        true,
+    );
+    assert_not_contains_entry(&ratings, "/tests/resources/files/eval-in-file.php");
+    assert_not_contains_entry(&ratings, "/tests/resources/files/error_reporting.php");
+}
+
+#[test]
+fn run_rules_with_builtin_changes_test() {
+    configure_logging(simplelog::LevelFilter::Error);
+    let configuration_file = format!(
+        "{}{}",
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/resources/rules/builtin_changes.yaml"
+    );
+    let rules = get_rules_from_paths(&[&configuration_file], true).unwrap();
+
+    assert!(rules.iter().any(|r| r.name() == "some custom typo3 rule"));
+    assert!(!rules.iter().any(|r| r.name().starts_with("typo3::")));
+    assert!(!rules.iter().any(|r| r.name() == "php::content::raprap1"));
+
+    let files = file_finder::find_files(format!("{}/tests", env!("CARGO_MANIFEST_DIR")), &rules);
+    let ratings = rate_entries(&files, &rules);
+
+    // error_reporting (NOTICE) + dezmond (overridden to MINOR)
+    assert_contains_entry_with_score(
+        &ratings,
+        Severity::NOTICE as isize + Severity::MINOR as isize,
+        "/tests/resources/files/dezmond.php",
+        true,
+    );
+    assert_not_contains_entry(
+        &ratings,
+        "/tests/resources/files/typo3/typo3temp/bad_file.php",
+    );
+
+    let rules = get_rules_from_paths(&[&configuration_file], false).unwrap();
+    assert_eq!(1, rules.len());
+}
+
+#[test]
+fn run_rules_with_rule_pack_test() {
+    configure_logging(simplelog::LevelFilter::Error);
+    let configuration_file = format!(
+        "{}{}",
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/resources/rules/builtin_changes.yaml"
+    );
+    let pack_file = format!(
+        "{}{}",
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/resources/rules/packs/php.yaml"
+    );
+    let builtin_rules = get_builtin_rules_from_packs(&[&pack_file]).unwrap();
+    let rules =
+        get_rules_from_paths_with_builtin(&[&configuration_file], Some(builtin_rules)).unwrap();
+
+    // The php pack on disk only contains the eval rule, the typo3 pack is disabled
+    assert!(!rules.iter().any(|r| r.name() == "php::content::dezmond"));
+    assert!(!rules.iter().any(|r| r.name().starts_with("typo3::")));
+
+    let files = file_finder::find_files(format!("{}/tests", env!("CARGO_MANIFEST_DIR")), &rules);
+    let ratings = rate_entries(&files, &rules);
+
+    assert_contains_entry_with_score(
+        &ratings,
+        Severity::MAJOR as isize,
+        "/tests/resources/files/eval-in-file.php",
+        true,
+    );
+    assert_not_contains_entry(&ratings, "/tests/resources/files/dezmond.php");
+}
+
+#[test]
+fn run_rule_examples_test() {
+    configure_logging(simplelog::LevelFilter::Error);
+    let configuration_file = format!(
+        "{}{}",
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/resources/rules/examples.yaml"
+    );
+    let rules = get_rules_from_paths(&[&configuration_file], true).unwrap();
+    let results = classifier::run_rule_examples(&rules);
+
+    assert_eq!(7, results.len());
+    let failed: Vec<(&str, &str)> = results
+        .iter()
+        .filter(|r| !r.passed())
+        .map(|r| (r.rule.name(), r.example.path.as_str()))
+        .collect();
+    assert_eq!(vec![("webshell file name", "/var/www/SHELL.php")], failed);
+}
+
+#[test]
+fn run_binary_content_test() {
+    configure_logging(simplelog::LevelFilter::Error);
+    let configuration_file = format!(
+        "{}{}",
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/resources/rules/binary.yaml"
+    );
+    let rules = get_rules_from_paths(&[&configuration_file], false).unwrap();
+    let files = file_finder::find_files(format!("{}/tests", env!("CARGO_MANIFEST_DIR")), &rules);
+    let ratings = rate_entries(&files, &rules);
+
+    assert_contains_entry_with_score(
+        &ratings,
+        Severity::MINOR as isize + Severity::MAJOR as isize,
+        "/tests/resources/files/binary/dropper.bin",
+        true,
+    );
+}
@@ -57,6 +57,24 @@ pub fn assert_multiple_paths<D: DirEntryTrait>(matches: Vec<D>) {
     ));
 }
 
+/// Assert that the entries found in `tests/resources/files` have paths relative to the test dir
+pub fn assert_relative_paths<D: DirEntryTrait>(matches: Vec<D>) {
+    let entry = get_entry_for_path(
+        &matches,
+        format!(
+            "{}{}",
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/resources/files/tx_mocfilemanager.php"
+        ),
+    )
+    .expect("Entry must have been found");
+
+    assert_eq!(
+        Path::new("resources/files/tx_mocfilemanager.php"),
+        entry.relative_path()
+    );
+}
+
 pub fn assert_single_path<D: DirEntryTrait>(matches: Vec<D>) {
     assert_eq!(1, matches.len());
 